@@ -0,0 +1,177 @@
+use crate::calculator::CalcButtons;
+use crate::decimal::Decimal;
+use crate::number::Number;
+use crate::{sum_calc_operations, CalcEngine, CalcError, CalcOperations};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarginVar {
+    Cost,
+    Sell,
+    Margin, // Percent of the selling price that is profit
+}
+
+impl MarginVar {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MarginVar::Cost => "CST",
+            MarginVar::Sell => "SEL",
+            MarginVar::Margin => "MAR",
+        }
+    }
+}
+
+/// Business mode's tax rate, grand total and cost, sell and margin figures.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Business {
+    pub tax_rate: Decimal, // Percent, TAX+ adds it and TAX- takes it back off
+    pub grand_total: Decimal, // Every result = gives in business mode, until CLR GT
+    pub cost: Decimal,
+    pub sell: Decimal,
+    pub margin: Decimal,
+    pub last: Option<MarginVar>, // Stored or solved last, for the status line
+}
+
+impl Business {
+    pub fn new() -> Self {
+        Business {
+            tax_rate: Decimal::ZERO,
+            grand_total: Decimal::ZERO,
+            cost: Decimal::ZERO,
+            sell: Decimal::ZERO,
+            margin: Decimal::ZERO,
+            last: None,
+        }
+    }
+
+    pub fn set_tax_rate(&mut self, rate: Decimal) -> Result<(), CalcError> {
+        if rate.is_negative() {
+            return Err(CalcError::Domain);
+        }
+        self.tax_rate = rate;
+        Ok(())
+    }
+
+    fn tax_factor(&self, precision: u32) -> Result<Decimal, CalcError> { // 1.08 for 8%
+        Decimal::ONE.checked_add(self.tax_rate.checked_div(Decimal::from(100), precision)?)
+    }
+
+    pub fn tax_add(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+        value.checked_mul(self.tax_factor(precision)?, precision)
+    }
+
+    pub fn tax_remove(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> { // The price before tax was added
+        value.checked_div(self.tax_factor(precision)?, precision)
+    }
+
+    pub fn accumulate(&mut self, value: Decimal) -> Result<(), CalcError> {
+        self.grand_total = self.grand_total.checked_add(value)?;
+        Ok(())
+    }
+
+    pub fn get(&self, var: MarginVar) -> Decimal {
+        match var {
+            MarginVar::Cost => self.cost,
+            MarginVar::Sell => self.sell,
+            MarginVar::Margin => self.margin,
+        }
+    }
+
+    pub fn set(&mut self, var: MarginVar, value: Decimal) {
+        match var {
+            MarginVar::Cost => self.cost = value,
+            MarginVar::Sell => self.sell = value,
+            MarginVar::Margin => self.margin = value,
+        }
+        self.last = Some(var);
+    }
+
+    pub fn solve(&self, var: MarginVar, precision: u32) -> Result<Decimal, CalcError> { // From the other two
+        let hundred: Decimal = Decimal::from(100);
+        let kept: Decimal = hundred.checked_sub(self.margin)?.checked_div(hundred, precision)?; // Share of the price that is cost
+        match var {
+            MarginVar::Cost => self.sell.checked_mul(kept, precision),
+            MarginVar::Sell => self.cost.checked_div(kept, precision),
+            MarginVar::Margin => self.sell.checked_sub(self.cost)?.checked_div(self.sell, precision)?.checked_mul(hundred, precision),
+        }
+    }
+
+    pub fn label(&self) -> String { // "MAR TAX 8%", the figure last set and the tax rate
+        match self.last {
+            Some(var) => format!("{} TAX {}%", var.label(), self.tax_rate),
+            None => format!("TAX {}%", self.tax_rate),
+        }
+    }
+}
+
+impl Default for Business {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalcEngine {
+    pub(crate) fn handle_business(&mut self, button: CalcButtons) -> bool { // Tax, margins and the grand total, in business mode
+        match button {
+            CalcButtons::TaxAdd => sum_calc_operations(self, CalcOperations::TaxAdd),
+            CalcButtons::TaxRemove => sum_calc_operations(self, CalcOperations::TaxRemove),
+            CalcButtons::TaxRate => self.tax_rate(),
+            CalcButtons::Cost => sum_calc_operations(self, CalcOperations::Margin(MarginVar::Cost)),
+            CalcButtons::Sell => sum_calc_operations(self, CalcOperations::Margin(MarginVar::Sell)),
+            CalcButtons::Margin => sum_calc_operations(self, CalcOperations::Margin(MarginVar::Margin)),
+            CalcButtons::Markup => sum_calc_operations(self, CalcOperations::Markup),
+            CalcButtons::PercentChange => sum_calc_operations(self, CalcOperations::PercentChange),
+            CalcButtons::GrandTotal => sum_calc_operations(self, CalcOperations::GrandTotal),
+            CalcButtons::GrandTotalClear => self.business.grand_total = Decimal::ZERO,
+            _ => return false,
+        }
+        true
+    }
+
+    pub(crate) fn tax(&mut self, add: bool) { // TAX+ and TAX- on the value showing
+        let value: Result<Decimal, CalcError> = self.showing().to_decimal(self.sum.precision).and_then(|value| match add {
+            true => self.business.tax_add(value, self.sum.precision),
+            false => self.business.tax_remove(value, self.sum.precision),
+        });
+        match value {
+            Ok(value) => self.show_result(Number::Real(value)),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn tax_rate(&mut self) { // Set from the value typed, or shown
+        if self.has_entry() {
+            let set: Result<(), CalcError> = self.entry().to_decimal(self.sum.precision).and_then(|rate| self.business.set_tax_rate(rate));
+            if let Err(error) = set {
+                self.error = Some(error);
+                return;
+            }
+        }
+        self.show_result(Number::Real(self.business.tax_rate));
+        self.index = CalcOperations::Sum;
+    }
+
+    pub(crate) fn margin_key(&mut self, var: MarginVar) { // As the TVM keys, a value typed is stored and nothing typed solves
+        let value: Result<Decimal, CalcError> = if self.has_entry() {
+            self.entry().to_decimal(self.sum.precision)
+        } else {
+            self.business.solve(var, self.sum.precision)
+        };
+        match value {
+            Ok(value) => {
+                self.business.set(var, value);
+                self.show_result(Number::Real(value));
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub(crate) fn collect_grand_total(&mut self) { // Each result = gives, while in business mode
+        if self.error.is_some() {
+            return;
+        }
+        let collected: Result<(), CalcError> = self.sum.sum.to_decimal(self.sum.precision).and_then(|value| self.business.accumulate(value));
+        if let Err(error) = collected {
+            self.error = Some(error);
+        }
+    }
+}
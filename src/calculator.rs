@@ -1,11 +1,9 @@
 use bevy::prelude::*;
 
-use crate::OpIndex;
+use crate::{CalcMode, OpIndex};
 use crate::cam_calc_screen::CalcUIMaterialHandle;
 use crate::game_env::{CountdownCycle, Interactable, Loaded};
 
-use std::collections::HashMap;
-
 /// This system starts the countdown when the mouse is clicked.
 pub fn cycle_screen_albedo(
     mut countdown: ResMut<CountdownCycle>,
@@ -152,7 +150,6 @@ impl CurrentMeshColor {
                         warn!("Material not found or invalid for handle: {:?}", material_handle);                    }
                 } else {
                     warn!("Could not get material handle for child: {:?}", child);                }
-            } else {
             }
 
             // Recursively check grandchildren
@@ -213,7 +210,7 @@ impl Default for ScreenAlbedoState {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CalcButtons {
     Sum,
     Clear,
@@ -235,37 +232,480 @@ pub enum CalcButtons {
     NoneButtonBody,
     NoneButtonScreen,
     NoneButtonLightPanel,
+    // Keyboard-only keys, the calculator model has no mesh for these
+    EntryMode,
+    OpenParen,
+    CloseParen,
+    MemoryClear,
+    MemoryRecall,
+    MemoryAdd,
+    MemorySubtract,
+    SignToggle,
+    Backspace,
+    ClearEntry,
+    // Scientific layer, reached through 2nd on the keypad
+    Second,
+    SquareRoot,
+    Square,
+    Power,
+    Reciprocal,
+    Sin,
+    Cos,
+    Tan,
+    ArcSin,
+    ArcCos,
+    ArcTan,
+    Log10,
+    Ln,
+    Exp,
+    Factorial,
+    AngleMode,
+    // Programmer mode, its own 2nd layer
+    Mode,
+    HexA,
+    HexB,
+    HexC,
+    HexD,
+    HexE,
+    HexF,
+    And,
+    Or,
+    Xor,
+    Not,
+    ShiftLeft,
+    ShiftRight,
+    Modulo,
+    Base,
+    WordSize,
+    Signed,
+    FractionToggle,
+    // Complex mode
+    Imaginary,
+    PolarToggle,
+    Magnitude,
+    Argument,
+    Conjugate,
+    // RPN stack
+    Swap,
+    RollDown,
+    LastX,
+    // Conversion mode
+    UnitCategory,
+    UnitFrom,
+    UnitTo,
+    UnitSwap,
+    Convert,
+    // Statistics mode
+    StatAdd,    // Σ+
+    StatRemove, // Σ-
+    StatPair,   // Ends the x of an x,y pair
+    StatReviewNext,
+    StatReviewPrev,
+    StatClear,  // CLΣ
+    StatCount,
+    StatMean,
+    StatSampleSd,
+    StatPopulationSd,
+    StatSum,
+    StatSumSquares,
+    StatSlope,
+    StatIntercept,
+    StatCorrelation,
+    Constant, // Opens the CONST list
+    Store,    // STO, then a register A to Z
+    Recall,   // RCL, then a register A to Z
+    Register(usize), // A letter key while STO or RCL waits, 0 is A
+    // Undo and redo, Ctrl+Z and Ctrl+Y
+    Undo,
+    Redo,
+    // Keystroke programming
+    Program,    // PRGM, then a digit for the program to record, and again to end
+    RunStop,    // R/S
+    SingleStep, // SST
+    Execute,    // XEQ, then a digit for the program to run
+    Label,      // LBL, then a digit
+    Goto,       // GTO, then a digit
+    Test,       // Then 1 x=0?, 2 x≠0?, 3 x>0? or 4 x<0?
+    // Finance mode
+    TvmPeriods,      // N
+    TvmRate,         // I/Y
+    TvmPresentValue, // PV
+    TvmPayment,      // PMT
+    TvmFutureValue,  // FV
+    TvmPerYear,      // P/Y
+    TvmBegin,        // BGN / END
+    TvmAmortize,     // AMORT, the schedule viewer
+    TvmClear,        // CLR TVM
+    // Business mode
+    TaxAdd,          // TAX+
+    TaxRemove,       // TAX-
+    TaxRate,         // Sets the tax rate, or shows it
+    Cost,            // CST
+    Sell,            // SEL
+    Margin,          // MAR
+    Markup,          // MU
+    PercentChange,   // Δ%
+    GrandTotal,      // GT
+    GrandTotalClear, // CLR GT
+    Percent,         // %, Shift+5 on the keyboard
 }
 
+const KEYPAD: &[(u32, CalcButtons)] = &[ // Mesh entity indices past OpIndex::entities, for the keys the model has
+    (40, CalcButtons::Sum),
+    (46, CalcButtons::Clear),
+    (48, CalcButtons::Decimal),
+    (41, CalcButtons::Add),
+    (42, CalcButtons::Subtract),
+    (43, CalcButtons::Multiply),
+    (44, CalcButtons::Divide),
+    (47, CalcButtons::Num0),
+    (50, CalcButtons::Num1),
+    (51, CalcButtons::Num2),
+    (52, CalcButtons::Num3),
+    (54, CalcButtons::Num4),
+    (55, CalcButtons::Num5),
+    (56, CalcButtons::Num6),
+    (58, CalcButtons::Num7),
+    (59, CalcButtons::Num8),
+    (60, CalcButtons::Num9),
+    (37, CalcButtons::NoneButtonBody),
+    (62, CalcButtons::NoneButtonScreen),
+    (63, CalcButtons::NoneButtonLightPanel),
+];
+
+const NAMED: &[CalcButtons] = &[ // Every key but Register, which takes a number, for from_name; a test checks it against the enum
+    CalcButtons::Sum, CalcButtons::Clear, CalcButtons::Decimal, CalcButtons::Add, CalcButtons::Subtract,
+    CalcButtons::Multiply, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Num1, CalcButtons::Num2,
+    CalcButtons::Num3, CalcButtons::Num4, CalcButtons::Num5, CalcButtons::Num6, CalcButtons::Num7,
+    CalcButtons::Num8, CalcButtons::Num9, CalcButtons::NoneButtonBody, CalcButtons::NoneButtonScreen,
+    CalcButtons::NoneButtonLightPanel, CalcButtons::EntryMode, CalcButtons::OpenParen, CalcButtons::CloseParen,
+    CalcButtons::MemoryClear, CalcButtons::MemoryRecall, CalcButtons::MemoryAdd, CalcButtons::MemorySubtract,
+    CalcButtons::SignToggle, CalcButtons::Backspace, CalcButtons::ClearEntry, CalcButtons::Second,
+    CalcButtons::SquareRoot, CalcButtons::Square, CalcButtons::Power, CalcButtons::Reciprocal, CalcButtons::Sin,
+    CalcButtons::Cos, CalcButtons::Tan, CalcButtons::ArcSin, CalcButtons::ArcCos, CalcButtons::ArcTan,
+    CalcButtons::Log10, CalcButtons::Ln, CalcButtons::Exp, CalcButtons::Factorial, CalcButtons::AngleMode,
+    CalcButtons::Mode, CalcButtons::HexA, CalcButtons::HexB, CalcButtons::HexC, CalcButtons::HexD,
+    CalcButtons::HexE, CalcButtons::HexF, CalcButtons::And, CalcButtons::Or, CalcButtons::Xor, CalcButtons::Not,
+    CalcButtons::ShiftLeft, CalcButtons::ShiftRight, CalcButtons::Modulo, CalcButtons::Base,
+    CalcButtons::WordSize, CalcButtons::Signed, CalcButtons::FractionToggle, CalcButtons::Imaginary,
+    CalcButtons::PolarToggle, CalcButtons::Magnitude, CalcButtons::Argument, CalcButtons::Conjugate,
+    CalcButtons::Swap, CalcButtons::RollDown, CalcButtons::LastX, CalcButtons::UnitCategory,
+    CalcButtons::UnitFrom, CalcButtons::UnitTo, CalcButtons::UnitSwap, CalcButtons::Convert,
+    CalcButtons::StatAdd, CalcButtons::StatRemove, CalcButtons::StatPair, CalcButtons::StatReviewNext,
+    CalcButtons::StatReviewPrev, CalcButtons::StatClear, CalcButtons::StatCount, CalcButtons::StatMean,
+    CalcButtons::StatSampleSd, CalcButtons::StatPopulationSd, CalcButtons::StatSum, CalcButtons::StatSumSquares,
+    CalcButtons::StatSlope, CalcButtons::StatIntercept, CalcButtons::StatCorrelation, CalcButtons::Constant,
+    CalcButtons::Store, CalcButtons::Recall, CalcButtons::Undo, CalcButtons::Redo, CalcButtons::Program,
+    CalcButtons::RunStop, CalcButtons::SingleStep, CalcButtons::Execute, CalcButtons::Label, CalcButtons::Goto,
+    CalcButtons::Test, CalcButtons::TvmPeriods, CalcButtons::TvmRate, CalcButtons::TvmPresentValue,
+    CalcButtons::TvmPayment, CalcButtons::TvmFutureValue, CalcButtons::TvmPerYear, CalcButtons::TvmBegin,
+    CalcButtons::TvmAmortize, CalcButtons::TvmClear, CalcButtons::TaxAdd, CalcButtons::TaxRemove,
+    CalcButtons::TaxRate, CalcButtons::Cost, CalcButtons::Sell, CalcButtons::Margin, CalcButtons::Markup,
+    CalcButtons::PercentChange, CalcButtons::GrandTotal, CalcButtons::GrandTotalClear, CalcButtons::Percent,
+];
+
 impl CalcButtons {
     pub fn from_index(
-        op_index: &mut ResMut<OpIndex>,
+        op_index: &OpIndex,
         index: u32,
     ) -> Option<CalcButtons> {
-        let mut button_map = HashMap::new();
-
-        button_map.insert(40 + op_index.entities, CalcButtons::Sum);
-        button_map.insert(46 + op_index.entities, CalcButtons::Clear);
-        button_map.insert(48 + op_index.entities, CalcButtons::Decimal);
-        button_map.insert(41 + op_index.entities, CalcButtons::Add);
-        button_map.insert(42 + op_index.entities, CalcButtons::Subtract);
-        button_map.insert(43 + op_index.entities, CalcButtons::Multiply);
-        button_map.insert(44 + op_index.entities, CalcButtons::Divide);
-        button_map.insert(47 + op_index.entities, CalcButtons::Num0);
-        button_map.insert(50 + op_index.entities, CalcButtons::Num1);
-        button_map.insert(51 + op_index.entities, CalcButtons::Num2);
-        button_map.insert(52 + op_index.entities, CalcButtons::Num3);
-        button_map.insert(54 + op_index.entities, CalcButtons::Num4);
-        button_map.insert(55 + op_index.entities, CalcButtons::Num5);
-        button_map.insert(56 + op_index.entities, CalcButtons::Num6);
-        button_map.insert(58 + op_index.entities, CalcButtons::Num7);
-        button_map.insert(59 + op_index.entities, CalcButtons::Num8);
-        button_map.insert(60 + op_index.entities, CalcButtons::Num9);
-        button_map.insert(37 + op_index.entities, CalcButtons::NoneButtonBody);
-        button_map.insert(62 + op_index.entities, CalcButtons::NoneButtonScreen);
-        button_map.insert(63 + op_index.entities, CalcButtons::NoneButtonLightPanel);
-    
-        button_map.get(&index).cloned()
+        KEYPAD.iter()
+            .find(|(offset, _)| offset + op_index.entities == index)
+            .map(|(_, button)| button.clone())
+    }
+
+    pub fn to_index(&self, op_index: &OpIndex) -> Option<u32> { // The mesh entity index of a key the model has
+        KEYPAD.iter()
+            .find(|(_, button)| button == self)
+            .map(|(offset, _)| offset + op_index.entities)
+    }
+
+    pub fn from_key(key: &KeyCode) -> Option<CalcButtons> {
+        match key {
+            KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Equal => Some(CalcButtons::Sum),
+            KeyCode::Escape => Some(CalcButtons::Clear),
+            KeyCode::Period | KeyCode::NumpadDecimal => Some(CalcButtons::Decimal),
+            KeyCode::NumpadAdd => Some(CalcButtons::Add),
+            KeyCode::Minus | KeyCode::NumpadSubtract => Some(CalcButtons::Subtract),
+            KeyCode::NumpadMultiply => Some(CalcButtons::Multiply),
+            KeyCode::Slash | KeyCode::NumpadDivide => Some(CalcButtons::Divide),
+            KeyCode::Digit0 | KeyCode::Numpad0 => Some(CalcButtons::Num0),
+            KeyCode::Digit1 | KeyCode::Numpad1 => Some(CalcButtons::Num1),
+            KeyCode::Digit2 | KeyCode::Numpad2 => Some(CalcButtons::Num2),
+            KeyCode::Digit3 | KeyCode::Numpad3 => Some(CalcButtons::Num3),
+            KeyCode::Digit4 | KeyCode::Numpad4 => Some(CalcButtons::Num4),
+            KeyCode::Digit5 | KeyCode::Numpad5 => Some(CalcButtons::Num5),
+            KeyCode::Digit6 | KeyCode::Numpad6 => Some(CalcButtons::Num6),
+            KeyCode::Digit7 | KeyCode::Numpad7 => Some(CalcButtons::Num7),
+            KeyCode::Digit8 | KeyCode::Numpad8 => Some(CalcButtons::Num8),
+            KeyCode::Digit9 | KeyCode::Numpad9 => Some(CalcButtons::Num9),
+            KeyCode::F1 => Some(CalcButtons::EntryMode),
+            KeyCode::BracketLeft => Some(CalcButtons::OpenParen),
+            KeyCode::BracketRight => Some(CalcButtons::CloseParen),
+            KeyCode::KeyC => Some(CalcButtons::MemoryClear),
+            KeyCode::KeyR => Some(CalcButtons::MemoryRecall),
+            KeyCode::KeyM => Some(CalcButtons::MemoryAdd),
+            KeyCode::KeyN => Some(CalcButtons::MemorySubtract),
+            KeyCode::F9 => Some(CalcButtons::SignToggle),
+            KeyCode::Backspace => Some(CalcButtons::Backspace),
+            KeyCode::Delete => Some(CalcButtons::ClearEntry),
+            KeyCode::Tab => Some(CalcButtons::Second),
+            KeyCode::F2 => Some(CalcButtons::Mode),
+            KeyCode::F3 => Some(CalcButtons::Base),
+            KeyCode::F4 => Some(CalcButtons::WordSize),
+            KeyCode::F5 => Some(CalcButtons::Signed),
+            KeyCode::F6 => Some(CalcButtons::FractionToggle),
+            KeyCode::F7 => Some(CalcButtons::PolarToggle),
+            KeyCode::F8 => Some(CalcButtons::Convert),
+            KeyCode::KeyI => Some(CalcButtons::Imaginary),
+            KeyCode::KeyX => Some(CalcButtons::Swap),
+            KeyCode::KeyD => Some(CalcButtons::RollDown),
+            KeyCode::KeyL => Some(CalcButtons::LastX),
+            KeyCode::KeyK => Some(CalcButtons::Constant),
+            KeyCode::KeyO => Some(CalcButtons::Store),
+            KeyCode::KeyP => Some(CalcButtons::Recall),
+            KeyCode::KeyS => Some(CalcButtons::StatAdd),
+            KeyCode::Comma => Some(CalcButtons::StatPair),
+            KeyCode::ArrowDown => Some(CalcButtons::StatReviewNext),
+            KeyCode::ArrowUp => Some(CalcButtons::StatReviewPrev),
+            KeyCode::F10 => Some(CalcButtons::Program),
+            KeyCode::F11 => Some(CalcButtons::SingleStep),
+            KeyCode::F12 => Some(CalcButtons::RunStop),
+            KeyCode::KeyQ => Some(CalcButtons::Execute),
+            KeyCode::KeyB => Some(CalcButtons::Label),
+            KeyCode::KeyG => Some(CalcButtons::Goto),
+            KeyCode::KeyT => Some(CalcButtons::Test),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CalcButtons> { // The key's Debug name, as programs are saved
+        if let Some(register) = name.strip_prefix("Register(").and_then(|rest| rest.strip_suffix(')')) {
+            return register.parse::<usize>().ok().map(CalcButtons::Register);
+        }
+        NAMED.iter().find(|button| format!("{:?}", button) == name).cloned()
+    }
+
+    pub fn from_register_key(key: &KeyCode) -> Option<CalcButtons> { // Letters, in place of from_key while STO or RCL waits
+        let letters: [KeyCode; 26] = [
+            KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF, KeyCode::KeyG,
+            KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN,
+            KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR, KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU,
+            KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY, KeyCode::KeyZ,
+        ];
+        letters.iter().position(|letter| letter == key).map(CalcButtons::Register)
+    }
+
+    pub fn from_ctrl_key(key: &KeyCode) -> Option<CalcButtons> { // With Ctrl held, in place of from_key
+        match key {
+            KeyCode::KeyZ => Some(CalcButtons::Undo),
+            KeyCode::KeyY => Some(CalcButtons::Redo),
+            _ => None,
+        }
+    }
+
+    pub fn from_shift_key(key: &KeyCode) -> Option<CalcButtons> { // With Shift held, ahead of from_key
+        match key {
+            KeyCode::Digit5 => Some(CalcButtons::Percent),
+            KeyCode::Equal => Some(CalcButtons::Add), // + and * share their keys with = and 8
+            KeyCode::Digit8 => Some(CalcButtons::Multiply),
+            _ => None,
+        }
+    }
+
+    pub fn digit(&self) -> Option<i32> {
+        match self {
+            CalcButtons::Num0 => Some(0),
+            CalcButtons::Num1 => Some(1),
+            CalcButtons::Num2 => Some(2),
+            CalcButtons::Num3 => Some(3),
+            CalcButtons::Num4 => Some(4),
+            CalcButtons::Num5 => Some(5),
+            CalcButtons::Num6 => Some(6),
+            CalcButtons::Num7 => Some(7),
+            CalcButtons::Num8 => Some(8),
+            CalcButtons::Num9 => Some(9),
+            CalcButtons::HexA => Some(10),
+            CalcButtons::HexB => Some(11),
+            CalcButtons::HexC => Some(12),
+            CalcButtons::HexD => Some(13),
+            CalcButtons::HexE => Some(14),
+            CalcButtons::HexF => Some(15),
+            _ => None,
+        }
+    }
+
+    pub fn mode(&self) -> Option<CalcMode> { // The only mode a key works in, for the keys a mode adds
+        match self {
+            CalcButtons::Base | CalcButtons::WordSize | CalcButtons::Signed => Some(CalcMode::Programmer),
+            CalcButtons::StatAdd
+            | CalcButtons::StatRemove
+            | CalcButtons::StatPair
+            | CalcButtons::StatReviewNext
+            | CalcButtons::StatReviewPrev
+            | CalcButtons::StatClear
+            | CalcButtons::StatCount
+            | CalcButtons::StatMean
+            | CalcButtons::StatSampleSd
+            | CalcButtons::StatPopulationSd
+            | CalcButtons::StatSum
+            | CalcButtons::StatSumSquares
+            | CalcButtons::StatSlope
+            | CalcButtons::StatIntercept
+            | CalcButtons::StatCorrelation => Some(CalcMode::Stats),
+            CalcButtons::TvmPeriods
+            | CalcButtons::TvmRate
+            | CalcButtons::TvmPresentValue
+            | CalcButtons::TvmPayment
+            | CalcButtons::TvmFutureValue
+            | CalcButtons::TvmPerYear
+            | CalcButtons::TvmBegin
+            | CalcButtons::TvmAmortize
+            | CalcButtons::TvmClear => Some(CalcMode::Finance),
+            CalcButtons::TaxAdd
+            | CalcButtons::TaxRemove
+            | CalcButtons::TaxRate
+            | CalcButtons::Cost
+            | CalcButtons::Sell
+            | CalcButtons::Margin
+            | CalcButtons::Markup
+            | CalcButtons::PercentChange
+            | CalcButtons::GrandTotal
+            | CalcButtons::GrandTotalClear => Some(CalcMode::Business),
+            _ => None,
+        }
+    }
+
+    pub fn second(&self, mode: CalcMode) -> Option<CalcButtons> { // What a keypad key does after 2nd
+        if mode == CalcMode::Programmer {
+            return self.programmer_second();
+        }
+        let layer = match mode {
+            CalcMode::Complex => self.complex_second(),
+            CalcMode::Convert | CalcMode::Currency => self.convert_second(),
+            CalcMode::Stats => self.stats_second(),
+            CalcMode::Finance => self.finance_second(),
+            CalcMode::Business => self.business_second(),
+            _ => None,
+        };
+        if layer.is_some() {
+            return layer;
+        }
+        match self {
+            CalcButtons::Num1 => Some(CalcButtons::Sin),
+            CalcButtons::Num2 => Some(CalcButtons::Cos),
+            CalcButtons::Num3 => Some(CalcButtons::Tan),
+            CalcButtons::Num4 => Some(CalcButtons::ArcSin),
+            CalcButtons::Num5 => Some(CalcButtons::ArcCos),
+            CalcButtons::Num6 => Some(CalcButtons::ArcTan),
+            CalcButtons::Num7 => Some(CalcButtons::Log10),
+            CalcButtons::Num8 => Some(CalcButtons::Ln),
+            CalcButtons::Num9 => Some(CalcButtons::Exp),
+            CalcButtons::Num0 => Some(CalcButtons::Factorial),
+            CalcButtons::Add => Some(CalcButtons::Square),
+            CalcButtons::Subtract => Some(CalcButtons::SquareRoot),
+            CalcButtons::Multiply => Some(CalcButtons::Power),
+            CalcButtons::Divide => Some(CalcButtons::Reciprocal),
+            CalcButtons::Decimal => Some(CalcButtons::AngleMode),
+            CalcButtons::Sum => Some(CalcButtons::FractionToggle),
+            _ => None,
+        }
+    }
+
+    fn programmer_second(&self) -> Option<CalcButtons> {
+        match self {
+            CalcButtons::Num1 => Some(CalcButtons::HexA),
+            CalcButtons::Num2 => Some(CalcButtons::HexB),
+            CalcButtons::Num3 => Some(CalcButtons::HexC),
+            CalcButtons::Num4 => Some(CalcButtons::HexD),
+            CalcButtons::Num5 => Some(CalcButtons::HexE),
+            CalcButtons::Num6 => Some(CalcButtons::HexF),
+            CalcButtons::Num7 => Some(CalcButtons::And),
+            CalcButtons::Num8 => Some(CalcButtons::Or),
+            CalcButtons::Num9 => Some(CalcButtons::Xor),
+            CalcButtons::Num0 => Some(CalcButtons::Not),
+            CalcButtons::Add => Some(CalcButtons::ShiftLeft),
+            CalcButtons::Subtract => Some(CalcButtons::ShiftRight),
+            CalcButtons::Multiply => Some(CalcButtons::Modulo),
+            CalcButtons::Divide => Some(CalcButtons::Base),
+            CalcButtons::Decimal => Some(CalcButtons::WordSize),
+            CalcButtons::Sum => Some(CalcButtons::Signed),
+            _ => None,
+        }
+    }
+
+    fn complex_second(&self) -> Option<CalcButtons> { // Only where it differs from the scientific layer
+        match self {
+            CalcButtons::Num0 => Some(CalcButtons::Imaginary),
+            CalcButtons::Num1 => Some(CalcButtons::Magnitude),
+            CalcButtons::Num2 => Some(CalcButtons::Argument),
+            CalcButtons::Num3 => Some(CalcButtons::Conjugate),
+            CalcButtons::Sum => Some(CalcButtons::PolarToggle),
+            _ => None,
+        }
+    }
+
+    fn convert_second(&self) -> Option<CalcButtons> { // Only where it differs from the scientific layer
+        match self {
+            CalcButtons::Num0 => Some(CalcButtons::Convert),
+            CalcButtons::Num1 => Some(CalcButtons::UnitCategory),
+            CalcButtons::Num2 => Some(CalcButtons::UnitFrom),
+            CalcButtons::Num3 => Some(CalcButtons::UnitTo),
+            CalcButtons::Sum => Some(CalcButtons::UnitSwap),
+            _ => None,
+        }
+    }
+
+    fn stats_second(&self) -> Option<CalcButtons> { // Only × is left to the scientific layer, as x^y
+        match self {
+            CalcButtons::Sum => Some(CalcButtons::StatAdd),
+            CalcButtons::Num0 => Some(CalcButtons::StatRemove),
+            CalcButtons::Decimal => Some(CalcButtons::StatPair),
+            CalcButtons::Num1 => Some(CalcButtons::StatCount),
+            CalcButtons::Num2 => Some(CalcButtons::StatMean),
+            CalcButtons::Num3 => Some(CalcButtons::StatSampleSd),
+            CalcButtons::Num4 => Some(CalcButtons::StatPopulationSd),
+            CalcButtons::Num5 => Some(CalcButtons::StatSum),
+            CalcButtons::Num6 => Some(CalcButtons::StatSumSquares),
+            CalcButtons::Num7 => Some(CalcButtons::StatSlope),
+            CalcButtons::Num8 => Some(CalcButtons::StatIntercept),
+            CalcButtons::Num9 => Some(CalcButtons::StatCorrelation),
+            CalcButtons::Add => Some(CalcButtons::StatReviewPrev),
+            CalcButtons::Subtract => Some(CalcButtons::StatReviewNext),
+            CalcButtons::Divide => Some(CalcButtons::StatClear),
+            _ => None,
+        }
+    }
+
+    fn finance_second(&self) -> Option<CalcButtons> { // Only where it differs from the scientific layer
+        match self {
+            CalcButtons::Num1 => Some(CalcButtons::TvmPeriods),
+            CalcButtons::Num2 => Some(CalcButtons::TvmRate),
+            CalcButtons::Num3 => Some(CalcButtons::TvmPresentValue),
+            CalcButtons::Num4 => Some(CalcButtons::TvmPayment),
+            CalcButtons::Num5 => Some(CalcButtons::TvmFutureValue),
+            CalcButtons::Num6 => Some(CalcButtons::TvmPerYear),
+            CalcButtons::Num7 => Some(CalcButtons::TvmBegin),
+            CalcButtons::Num8 => Some(CalcButtons::TvmAmortize),
+            CalcButtons::Num9 => Some(CalcButtons::TvmClear),
+            _ => None,
+        }
+    }
+
+    fn business_second(&self) -> Option<CalcButtons> { // Only where it differs from the scientific layer
+        match self {
+            CalcButtons::Num1 => Some(CalcButtons::TaxAdd),
+            CalcButtons::Num2 => Some(CalcButtons::TaxRemove),
+            CalcButtons::Num3 => Some(CalcButtons::TaxRate),
+            CalcButtons::Num4 => Some(CalcButtons::Cost),
+            CalcButtons::Num5 => Some(CalcButtons::Sell),
+            CalcButtons::Num6 => Some(CalcButtons::Margin),
+            CalcButtons::Num7 => Some(CalcButtons::Markup),
+            CalcButtons::Num8 => Some(CalcButtons::PercentChange),
+            CalcButtons::Num9 => Some(CalcButtons::GrandTotal),
+            CalcButtons::Num0 => Some(CalcButtons::GrandTotalClear),
+            _ => None,
+        }
     }
 
     pub fn button_info(&self) {
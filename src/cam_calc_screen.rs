@@ -6,28 +6,20 @@ use bevy::render::{
     },
 };
 
-use crate::{SumCurrent, SumVariable};
+use crate::CalcEngine;
 
 #[derive(Component)]
 pub struct SumText;
 
 pub fn update_sum_text(
-    mut sum: ResMut<SumCurrent>,
-    mut var: ResMut<SumVariable>,
+    engine: Res<CalcEngine>,
     mut query: Query<&mut Text, With<SumText>>,
 ) {
-    if sum.is_changed() {
-        // Only run this if the `sum` resource has been changed.
-        if *&sum.sum.to_string().len() > 8 {
-            for mut text in &mut query {
-                SumCurrent::zero(&mut sum);
-                var.clear();
-                text.sections[0].value = "S: Overload".to_owned();
-            }
-        } else {
-            for mut text in &mut query {
-                text.sections[0].value = "Sum: ".to_owned() + &sum.sum.to_string();
-            }
+    if engine.is_changed() {
+        // Only run this if the `engine` resource has been changed.
+        let screen = engine.screen();
+        for mut text in &mut query {
+            text.sections[0].value = screen.sum.clone();
         }
     }
 }
@@ -35,70 +27,56 @@ pub fn update_sum_text(
 #[derive(Component)]
 pub struct VarText;
 
+#[derive(Component)]
+pub struct BaseFontSize(pub f32); // The size a text was spawned at, which the RPN stack scales down from
+
+const STACK_FONT_SCALE: f32 = 0.45; // Three stack levels in the height of one input line
+
 pub fn update_var_text(
-    mut sum: ResMut<SumCurrent>,
-    mut var: ResMut<SumVariable>,
-    mut query: Query<&mut Text, With<VarText>>,
+    engine: Res<CalcEngine>,
+    mut query: Query<(&mut Text, &BaseFontSize), With<VarText>>,
 ) {
-    let res = if var.decimal_index > 0 {
-        let mut num: String = "".to_string();
-        let mut multiplier: String = ".".to_string();
-        
-        for i in 0..var.var.len() {
-            num += &var.var[i].to_string();
-        }
-        
-        for _ in 0..var.var.len() - var.decimal_index as usize {
-            multiplier += "0";
+    if engine.is_changed() {
+        // Only run this if the `engine` resource has been changed.
+        let screen = engine.screen();
+        let scale: f32 = if screen.stacked { STACK_FONT_SCALE } else { 1.0 };
+        for (mut text, base) in &mut query {
+            text.sections[0].value = screen.input.clone();
+            text.sections[0].style.font_size = base.0 * scale;
         }
-        
-        let popped_multiplier = {
-            let mut chars = multiplier.chars();
-            chars.next_back();
-            chars.as_str()
-        };
-
-        multiplier = popped_multiplier.to_string();
-        multiplier += "1";
-
-        let res_num: f64 = num.to_string().parse::<f64>().unwrap();
-        let res_mul: f64 = multiplier.to_string().parse::<f64>().unwrap();
-        let res_sum = res_num * res_mul;
-
-        res_sum
-    } else {
-        let mut num: String = "".to_string();
-        
-        for i in 0..var.var.len() {
-            num += &var.var[i].to_string();
-        }
-        
-        let res_sum: f64 = if var.var.len() == 0 {
-            0.0
-        } else {
-            num.to_string().parse::<f64>().unwrap()
-        };
-
-        res_sum
-    };
+    }
+}
+
+#[derive(Component)]
+pub struct StatusText;
 
-    let mut display_limiter = res.to_string();
-    while display_limiter.len() > 6 {
-        display_limiter.pop();
+pub fn update_status_text(
+    engine: Res<CalcEngine>,
+    mut query: Query<&mut Text, With<StatusText>>,
+) {
+    if engine.is_changed() {
+        let status = engine.display().status_line();
+        for mut text in &mut query {
+            text.sections[0].value = status.clone();
+        }
     }
+}
 
-    if var.is_changed() {
-        if res.to_string().len() > 6 {
-            for mut text in &mut query {
-                SumCurrent::zero(&mut sum);
-                var.clear();
-                text.sections[0].value = "I: Overload".to_owned();
-            }
-        } else {
-            // Only run this if the recompiled `Var` resource has been changed.
-            for mut text in &mut query {
-                text.sections[0].value = "Input: ".to_owned() + &display_limiter;
-            }
+const NOTICE_SECONDS: f32 = 2.0;
+
+pub fn expire_notice(
+    time: Res<Time>,
+    mut engine: ResMut<CalcEngine>,
+    mut shown: Local<(Option<String>, f32)>, // The notice showing, and for how long
+) {
+    if engine.notice != shown.0 {
+        *shown = (engine.notice.clone(), 0.0);
+        return;
+    }
+    if engine.notice.is_some() {
+        shown.1 += time.delta_seconds();
+        if shown.1 > NOTICE_SECONDS {
+            engine.notice = None;
         }
     }
 }
@@ -108,7 +86,7 @@ pub fn setup_calc_interface_projection(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut images: ResMut<Assets<Image>>,
     asset_server: Res<AssetServer>,
-    sum: Res<SumCurrent>,
+    engine: Res<CalcEngine>,
 ) {
     let size = Extent3d {
         width: 1024,
@@ -172,6 +150,17 @@ pub fn setup_calc_interface_projection(
             },
             TargetCamera(texture_camera),
         ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 60.0,
+                    color: Color::srgb(0.0, 0.0, 0.0),
+                },
+            ))
+            .insert(StatusText); // Indicators such as open parentheses depth
+        })
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
                 "Input: 0",
@@ -179,19 +168,17 @@ pub fn setup_calc_interface_projection(
                     font: font.clone(),
                     font_size: 110.0,
                     color: Color::srgb(0.0, 0.0, 0.0),
-                    ..default()
                 },
             ))
-            .insert(VarText); // Insert a marker component to easily query this later
+            .insert((VarText, BaseFontSize(110.0))); // Insert a marker component to easily query this later
         })
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
-                "Sum: ".to_owned() + &sum.sum.to_string(),
+                "Sum: ".to_owned() + &engine.display().sum,
                 TextStyle {
                     font: font.clone(),
                     font_size: 110.0,
                     color: Color::srgb(0.0, 0.0, 0.0),
-                    ..default()
                 },
             ))
             .insert(SumText); // Insert a marker component to easily query this later
@@ -1,17 +1,33 @@
+use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 
-use crate::SumCurrent;
+use crate::{CalcEngine, CalcMode};
 
-use crate::cam_calc_screen::{SumText, VarText};
+use crate::cam_calc_screen::{BaseFontSize, StatusText, SumText, VarText};
 
 #[derive(Component)]
 pub struct CameraUi;
 
+#[derive(Component)]
+pub struct RatesText;
+
+#[derive(Component)]
+pub struct HistoryPanel;
+
+#[derive(Component)]
+pub struct HistoryRow(pub usize); // Rows down from the top of the panel, the newest entry first
+
+#[derive(Resource, Default)]
+pub struct HistoryHovered(pub bool); // The wheel scrolls the history, not the camera, while set
+
+const HISTORY_ROWS: usize = 8;
+const HISTORY_WIDTH: usize = 40; // Characters a row shows before it is cut short
+
 // UI with direct spawn
 pub fn setup_ui(
     mut commands: Commands, 
     asset_server: Res<AssetServer>,
-    sum: Res<SumCurrent>,
+    engine: Res<CalcEngine>,
 ) {
     let font = asset_server.load("fonts/MatrixtypeDisplay-KVELZ.ttf");
     let text_style = TextStyle {
@@ -76,7 +92,7 @@ pub fn setup_ui(
         parent.spawn(TextBundle {
             text: Text {
                 sections: vec![TextSection::new(
-                    "SHIFT: Zoom\nCTRL: Pan\nL-ALT: Rotate",
+                    "SHIFT: Zoom\nCTRL: Pan\nL-ALT: Rotate\nF1: Chain/Algebraic/RPN\nX D L: x<>y R-down LASTX\n[ ]: Parentheses\nM N R C: M+ M- MR MC\nF9: +/-\nDEL: CE  BKSP: Backspace\nTAB: 2nd\nF2: Mode  F3 F4 F5: Base, Word, Sign\nF6: Fraction/Decimal  F7: Rect/Polar  I: i\nF8: Convert\nS ,: Stats Σ+, x,y  UP DOWN: Review\nCTRL+Z CTRL+Y: Undo, Redo  K: Constants\nO P: STO RCL, then A-Z\nF10: PRGM  F12 F11: R/S SST  Q: XEQ\nSHIFT+5: %",
                     smaller_text_style.clone(),
                 )],
                 ..default()
@@ -91,6 +107,66 @@ pub fn setup_ui(
             ..default()
         });
     })
+    .with_children(|parent| {
+        parent.spawn(TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(
+                    "",
+                    smaller_text_style.clone(),
+                )],
+                ..default()
+            },
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Percent(1.5),
+                top: Val::Percent(2.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(RatesText); // When the rates in use were taken, in currency mode
+    })
+    .with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(1.5),
+                    top: Val::Percent(2.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert((HistoryPanel, Interaction::default())) // Hovered, the mouse wheel scrolls it
+            .with_children(|parent| {
+                parent.spawn(TextBundle {
+                    text: Text {
+                        sections: vec![TextSection::new(
+                            "History  PGUP PGDN: Scroll",
+                            smaller_text_style.clone(),
+                        )],
+                        ..default()
+                    },
+                    ..default()
+                });
+                for row in 0..HISTORY_ROWS {
+                    parent
+                        .spawn(TextBundle {
+                            text: Text {
+                                sections: vec![TextSection::new(
+                                    "",
+                                    smaller_text_style.clone(),
+                                )],
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert((HistoryRow(row), Interaction::default())); // Clicked, recalls its result
+                }
+            });
+    })
     .with_children(|parent| {
         parent
             .spawn(NodeBundle {
@@ -106,6 +182,20 @@ pub fn setup_ui(
                 ..default()
             })
             .with_children(|parent| {    
+                // Status Text, carries the memory indicator
+                parent
+                    .spawn(TextBundle {
+                        text: Text {
+                            sections: vec![TextSection::new(
+                                "",
+                                smaller_text_style.clone(),
+                            )],
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(StatusText);
+
                 // Input Text
                 parent
                     .spawn(TextBundle {
@@ -125,7 +215,7 @@ pub fn setup_ui(
                         },
                         ..default()
                     })
-                    .insert(VarText); // Insert a marker component to easily query this later
+                    .insert((VarText, BaseFontSize(smaller_text_style.font_size))); // Insert a marker component to easily query this later
 
                     
                 // Sum Text
@@ -133,7 +223,7 @@ pub fn setup_ui(
                     .spawn(TextBundle {
                         text: Text {
                             sections: vec![TextSection::new(
-                                "Sum: ".to_owned() + &sum.sum.to_string(),
+                                "Sum: ".to_owned() + &engine.display().sum,
                                 smaller_text_style.clone(),
                             )],
                             ..default()
@@ -143,4 +233,89 @@ pub fn setup_ui(
                     .insert(SumText); // Insert a marker component to easily query this later
             });
     });
-}
\ No newline at end of file
+}
+pub fn update_rates_text(
+    engine: Res<CalcEngine>,
+    mut query: Query<&mut Text, With<RatesText>>,
+) {
+    if engine.is_changed() {
+        let mut rates: String = String::new();
+        if engine.mode == CalcMode::Currency {
+            let table = &engine.currency.table;
+            rates = format!("Rates against {}", table.base);
+            for code in [&engine.currency.from, &engine.currency.to] {
+                let updated: &str = match table.updated(code) {
+                    Some(updated) => updated,
+                    None if *code == table.base => "base",
+                    None => "no rate",
+                };
+                rates += &format!("\n{}: {}", code, updated);
+            }
+        }
+        for mut text in &mut query {
+            text.sections[0].value = rates.clone();
+        }
+    }
+}
+
+pub fn update_history_text(
+    engine: Res<CalcEngine>,
+    mut query: Query<(&mut Text, &HistoryRow)>,
+) {
+    if engine.is_changed() {
+        for (mut text, row) in &mut query {
+            text.sections[0].value = match engine.history.visible(row.0) {
+                Some(index) => {
+                    let calculation = &engine.history.entries[index];
+                    let mut line: String = format!("{}  {}", calculation.time_label(), calculation.tape);
+                    if line.chars().count() > HISTORY_WIDTH {
+                        line = line.chars().take(HISTORY_WIDTH - 1).collect::<String>() + "…";
+                    }
+                    line
+                },
+                None => String::new(),
+            };
+        }
+    }
+}
+
+pub fn scroll_history(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut evr_scroll: EventReader<MouseWheel>,
+    panel_query: Query<&Interaction, Or<(With<HistoryPanel>, With<HistoryRow>)>>,
+    mut history_hovered: ResMut<HistoryHovered>,
+    mut engine: ResMut<CalcEngine>,
+) {
+    let hovered: bool = panel_query.iter().any(|interaction| *interaction != Interaction::None);
+    history_hovered.0 = hovered;
+    let mut steps: f32 = 0.0; // Up the wheel, back through older entries
+    for ev in evr_scroll.read() {
+        if hovered {
+            steps += ev.y.signum();
+        }
+    }
+    if keys.just_pressed(KeyCode::PageUp) {
+        steps += 1.0;
+    }
+    if keys.just_pressed(KeyCode::PageDown) {
+        steps -= 1.0;
+    }
+    if steps > 0.0 {
+        engine.history.scroll_back(HISTORY_ROWS);
+    } else if steps < 0.0 {
+        engine.history.scroll_forward();
+    }
+}
+
+pub fn recall_history(
+    query: Query<(&Interaction, &HistoryRow), Changed<Interaction>>,
+    mut engine: ResMut<CalcEngine>,
+) {
+    for (interaction, row) in &query {
+        if *interaction == Interaction::Pressed {
+            if let Some(index) = engine.history.visible(row.0) {
+                engine.recall_history(index);
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 use bevy_mod_raycast::prelude::*;
 
+use crate::cam_ui::HistoryHovered;
 use crate::game_env::Ground;
 
 #[derive(Component)]
@@ -104,6 +105,7 @@ impl Default for PanOrbitSettings {
 
 pub fn pan_orbit_camera(
     kbd: Res<ButtonInput<KeyCode>>,
+    history_hovered: Res<HistoryHovered>,
     mut evr_motion: EventReader<MouseMotion>,
     mut evr_scroll: EventReader<MouseWheel>,
     mut q_camera: Query<(
@@ -124,6 +126,9 @@ pub fn pan_orbit_camera(
     let mut total_scroll_lines = Vec2::ZERO;
     let mut total_scroll_pixels = Vec2::ZERO;
     for ev in evr_scroll.read() {
+        if history_hovered.0 {
+            continue; // The history panel takes the wheel
+        }
         match ev.unit {
             MouseScrollUnit::Line => {
                 total_scroll_lines.x += ev.x;
@@ -0,0 +1,140 @@
+use std::fmt;
+
+use crate::decimal::Decimal;
+use crate::{AngleMode, CalcError, PendingOp};
+
+/// A complex number `re + im·i`, both parts exact decimals.
+///
+/// Add, subtract and multiply stay exact; divide and anything through a
+/// square root round to the engine precision, the same as `Decimal`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Complex {
+    pub re: Decimal,
+    pub im: Decimal,
+}
+
+impl Complex {
+    pub fn new(re: Decimal, im: Decimal) -> Self {
+        Complex { re, im }
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+
+    pub fn checked_neg(&self) -> Result<Self, CalcError> {
+        Ok(Complex { re: self.re.checked_neg()?, im: self.im.checked_neg()? })
+    }
+
+    pub fn conj(&self) -> Result<Self, CalcError> {
+        Ok(Complex { re: self.re, im: self.im.checked_neg()? })
+    }
+
+    pub fn checked_add(&self, rhs: Complex) -> Result<Self, CalcError> {
+        Ok(Complex { re: self.re.checked_add(rhs.re)?, im: self.im.checked_add(rhs.im)? })
+    }
+
+    pub fn checked_sub(&self, rhs: Complex) -> Result<Self, CalcError> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, rhs: Complex, precision: u32) -> Result<Self, CalcError> {
+        // (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+        let re: Decimal = self.re.checked_mul(rhs.re, precision)?.checked_sub(self.im.checked_mul(rhs.im, precision)?)?;
+        let im: Decimal = self.re.checked_mul(rhs.im, precision)?.checked_add(self.im.checked_mul(rhs.re, precision)?)?;
+        Ok(Complex { re, im })
+    }
+
+    pub fn checked_div(&self, rhs: Complex, precision: u32) -> Result<Self, CalcError> {
+        // Multiply through by the conjugate so only the real c² + d² is divided by
+        let norm: Decimal = rhs.norm(precision)?;
+        if norm.is_zero() {
+            return Err(CalcError::DivideByZero);
+        }
+        let top: Complex = self.checked_mul(rhs.conj()?, precision)?;
+        Ok(Complex { re: top.re.checked_div(norm, precision)?, im: top.im.checked_div(norm, precision)? })
+    }
+
+    pub fn checked_powi(&self, exp: i128, precision: u32) -> Result<Self, CalcError> {
+        let mut res: Complex = Complex::from(Decimal::ONE);
+        let mut base: Complex = *self;
+        let mut power: u128 = exp.unsigned_abs();
+        while power > 0 { // Square and multiply
+            if power & 1 == 1 {
+                res = res.checked_mul(base, precision)?;
+            }
+            base = base.checked_mul(base, precision)?;
+            power >>= 1;
+        }
+        if exp < 0 {
+            Complex::from(Decimal::ONE).checked_div(res, precision)
+        } else {
+            Ok(res)
+        }
+    }
+
+    fn norm(&self, precision: u32) -> Result<Decimal, CalcError> { // Magnitude squared
+        self.re.checked_mul(self.re, precision)?.checked_add(self.im.checked_mul(self.im, precision)?)
+    }
+
+    pub fn magnitude(&self, precision: u32) -> Result<Decimal, CalcError> {
+        Decimal::from_f64(self.re.to_f64().hypot(self.im.to_f64()))?.round(precision)
+    }
+
+    pub fn argument(&self, precision: u32, angle_mode: AngleMode) -> Result<Decimal, CalcError> {
+        let radians: f64 = self.im.to_f64().atan2(self.re.to_f64());
+        Decimal::from_f64(angle_mode.angle(radians))?.round(precision)
+    }
+
+    pub fn sqrt(&self, precision: u32) -> Result<Self, CalcError> { // The principal root, so sqrt(-4) = 2i
+        let r: f64 = self.re.to_f64().hypot(self.im.to_f64());
+        let a: f64 = self.re.to_f64();
+        let re: f64 = ((r + a) / 2.0).sqrt();
+        let im: f64 = ((r - a) / 2.0).sqrt().copysign(if self.im.is_negative() { -1.0 } else { 1.0 });
+        Ok(Complex {
+            re: Decimal::from_f64(re)?.round(precision)?,
+            im: Decimal::from_f64(im)?.round(precision)?,
+        })
+    }
+
+    /// The operators complex numbers answer, `None` for the rest.
+    pub fn apply(&self, op: PendingOp, rhs: Complex, precision: u32) -> Option<Result<Self, CalcError>> {
+        match op {
+            PendingOp::Add => Some(self.checked_add(rhs)),
+            PendingOp::Subtract => Some(self.checked_sub(rhs)),
+            PendingOp::Multiply => Some(self.checked_mul(rhs, precision)),
+            PendingOp::Divide => Some(self.checked_div(rhs, precision)),
+            PendingOp::Power if rhs.is_real() && rhs.re.scale() == 0 => Some(self.checked_powi(rhs.re.mantissa(), precision)),
+            _ => None,
+        }
+    }
+
+    pub fn to_polar(&self, precision: u32, angle_mode: AngleMode) -> Result<(Decimal, Decimal), CalcError> {
+        Ok((self.magnitude(precision)?, self.argument(precision, angle_mode)?))
+    }
+}
+
+impl From<Decimal> for Complex {
+    fn from(value: Decimal) -> Self {
+        Complex { re: value, im: Decimal::ZERO }
+    }
+}
+
+impl fmt::Display for Complex { // "3+4i", "-2i", "1-i"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_real() {
+            return write!(f, "{}", self.re);
+        }
+        let magnitude: String = self.im.to_string().trim_start_matches('-').to_string(); // The sign is written on its own
+        let im: String = match magnitude.as_str() {
+            "1" => "i".to_string(),
+            _ => format!("{}i", magnitude),
+        };
+        let sign: &str = if self.im.is_negative() { "-" } else { "+" };
+        if self.re.is_zero() {
+            write!(f, "{}{}", if self.im.is_negative() { "-" } else { "" }, im)
+        } else {
+            write!(f, "{}{}{}", self.re, sign, im)
+        }
+    }
+}
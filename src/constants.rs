@@ -0,0 +1,112 @@
+use crate::csv::CsvTable;
+use crate::decimal::{Decimal, MAX_PRECISION};
+use crate::CalcEngine;
+
+pub const CONSTANTS_PATH: &str = "assets/constants.csv";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    pub symbol: String,
+    pub value: Decimal,
+    pub unit: String, // Empty for a pure number
+}
+
+/// The named constants read from `assets/constants.csv`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConstantTable {
+    pub constants: Vec<Constant>,
+}
+
+impl CsvTable for ConstantTable {
+    const PATH: &'static str = CONSTANTS_PATH;
+    const MISSING: &'static str = "Constant table not loaded, the CONST list is empty";
+
+    fn read_line(&mut self, fields: &[&str]) -> Result<(), String> { // name,symbol,value[,unit]
+        if fields.len() < 3 || fields.len() > 4 || fields[0].is_empty() {
+            return Err("expected name,symbol,value[,unit]".to_string());
+        }
+        let value: Decimal = parse_value(fields[2]).ok_or_else(|| "value is not a number".to_string())?;
+        if value.scale() > MAX_PRECISION {
+            return Err(format!("value needs more than {} decimal places", MAX_PRECISION)); // Would round to 0 in any calculation
+        }
+        self.constants.push(Constant {
+            name: fields[0].to_string(),
+            symbol: fields[1].to_string(),
+            value,
+            unit: fields.get(3).unwrap_or(&"").to_string(),
+        });
+        Ok(())
+    }
+
+    fn summary(&self) -> String {
+        format!("Constants loaded: {}", self.constants.len())
+    }
+
+    fn install(self, engine: &mut CalcEngine) {
+        engine.constants = ConstantPicker::new(self);
+    }
+}
+
+fn parse_value(text: &str) -> Option<Decimal> { // A decimal, with an optional exponent such as 6.626e-34
+    let (digits, exp) = match text.split_once(['e', 'E']) {
+        Some((digits, exp)) => (digits, exp.parse::<i32>().ok()?),
+        None => (text, 0),
+    };
+    let value: Decimal = digits.parse::<Decimal>().ok()?;
+    if exp >= 0 {
+        let scale: i128 = 10i128.checked_pow(exp as u32)?;
+        Some(Decimal::new(value.mantissa().checked_mul(scale)?, value.scale()))
+    } else {
+        Some(Decimal::new(value.mantissa(), value.scale() + exp.unsigned_abs()))
+    }
+}
+
+/// The constant table and where the CONST list is, while it is open.
+#[derive(Clone, Debug, Default)]
+pub struct ConstantPicker {
+    pub table: ConstantTable,
+    pub selected: usize, // Kept while closed, so the list reopens on the last pick
+    pub open: bool,
+}
+
+impl ConstantPicker {
+    pub fn new(table: ConstantTable) -> Self {
+        ConstantPicker {
+            table,
+            selected: 0,
+            open: false,
+        }
+    }
+
+    pub fn open(&mut self) -> bool { // Nothing to open without a table
+        self.open = !self.table.constants.is_empty();
+        self.open
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.table.constants.len().max(1);
+    }
+
+    pub fn prev(&mut self) {
+        let len: usize = self.table.constants.len().max(1);
+        self.selected = (self.selected + len - 1) % len;
+    }
+
+    pub fn jump(&mut self, index: usize) { // Straight to a place in the list, as far as it goes
+        self.selected = index.min(self.table.constants.len().saturating_sub(1));
+    }
+
+    pub fn current(&self) -> Option<&Constant> {
+        self.table.constants.get(self.selected)
+    }
+
+    pub fn line(&self) -> Option<String> { // "3/11 φ 1.6180339887498948482045868344"
+        let constant: &Constant = self.current()?;
+        Some(format!("{}/{} {} {}", self.selected + 1, self.table.constants.len(), constant.symbol, constant.value))
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use crate::CalcEngine;
+
+/// A table read from a comma-separated file in `assets/`, one record a line,
+/// with blank lines and lines starting '#' skipped.
+pub trait CsvTable: Default {
+    const PATH: &'static str;
+    const MISSING: &'static str; // What goes without when the file does not load, for the warning
+
+    fn read_line(&mut self, fields: &[&str]) -> Result<(), String>; // One line's trimmed fields, the error saying what is wrong
+    fn summary(&self) -> String; // Logged once loaded
+    fn install(self, engine: &mut CalcEngine);
+
+    fn finish(self) -> Result<Self, String> { // Checks that need the whole file
+        Ok(self)
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut table = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            table.read_line(&fields).map_err(|what| format!("line {}: {}", number + 1, what))?;
+        }
+        table.finish()
+    }
+
+    fn load(path: &str) -> Result<Self, String> {
+        let text: String = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        Self::parse(&text)
+    }
+}
+
+pub fn load_table<T: CsvTable>(
+    mut engine: ResMut<CalcEngine>,
+) {
+    match T::load(T::PATH) {
+        Ok(table) => {
+            info!("{}", table.summary());
+            table.install(&mut engine);
+        },
+        Err(err) => warn!("{}: {}", T::MISSING, err),
+    }
+}
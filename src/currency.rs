@@ -0,0 +1,142 @@
+use crate::csv::CsvTable;
+use crate::decimal::{Decimal, GUARD_DIGITS, MAX_PRECISION};
+use crate::{CalcEngine, CalcError};
+
+pub const RATES_PATH: &str = "assets/rates.csv";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rate {
+    pub code: String,
+    pub per_base: Decimal, // Units of this currency one unit of the base buys
+    pub updated: String,   // When the rate was taken, as written in the file
+}
+
+/// Exchange rates read from `assets/rates.csv`, all quoted against one base currency.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RateTable {
+    pub base: String,
+    pub rates: Vec<Rate>,
+}
+
+impl CsvTable for RateTable {
+    const PATH: &'static str = RATES_PATH;
+    const MISSING: &'static str = "Rate table not loaded, currency mode has no rates";
+
+    fn read_line(&mut self, fields: &[&str]) -> Result<(), String> { // A base,code line then code,per base,updated lines
+        match fields[..] {
+            ["base", code] => self.base = code.to_string(),
+            [code, per_base, updated] => {
+                let per_base: Decimal = match per_base.parse::<Decimal>() {
+                    Ok(rate) if !rate.is_zero() && !rate.is_negative() => rate,
+                    _ => return Err("rate is not a positive number".to_string()),
+                };
+                self.rates.push(Rate {
+                    code: code.to_string(),
+                    per_base,
+                    updated: updated.to_string(),
+                });
+            },
+            _ => return Err("expected base,code or code,per base,updated".to_string()),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self, String> {
+        if self.base.is_empty() {
+            return Err("no base currency line".to_string());
+        }
+        Ok(self)
+    }
+
+    fn summary(&self) -> String {
+        format!("Rates loaded: {} against {}", self.rates.len(), self.base)
+    }
+
+    fn install(self, engine: &mut CalcEngine) {
+        engine.currency = CurrencyConverter::new(self);
+    }
+}
+
+impl RateTable {
+    pub fn codes(&self) -> Vec<&str> { // The base first, then the rates in file order
+        let mut codes: Vec<&str> = vec![&self.base];
+        codes.extend(self.rates.iter().map(|rate| rate.code.as_str()).filter(|code| *code != self.base));
+        codes
+    }
+
+    pub fn per_base(&self, code: &str) -> Result<Decimal, CalcError> {
+        if code == self.base {
+            return Ok(Decimal::ONE);
+        }
+        match self.rates.iter().find(|rate| rate.code == code) {
+            Some(rate) => Ok(rate.per_base),
+            None => Err(CalcError::NoRate),
+        }
+    }
+
+    pub fn updated(&self, code: &str) -> Option<&str> {
+        self.rates.iter().find(|rate| rate.code == code).map(|rate| rate.updated.as_str())
+    }
+}
+
+/// The rate table and which pair of currencies currency mode converts between.
+#[derive(Clone, Debug)]
+pub struct CurrencyConverter {
+    pub table: RateTable,
+    pub from: String,
+    pub to: String,
+}
+
+impl CurrencyConverter {
+    pub fn new(table: RateTable) -> Self {
+        let codes: Vec<&str> = table.codes();
+        let from: String = codes[0].to_string();
+        let to: String = codes.get(1).unwrap_or(&codes[0]).to_string();
+        CurrencyConverter { table, from, to }
+    }
+
+    fn next(&self, code: &str) -> String { // Codes no longer in the table start over from the base
+        if self.table.base.is_empty() {
+            return code.to_string(); // Nothing loaded to pick from
+        }
+        let codes: Vec<&str> = self.table.codes();
+        match codes.iter().position(|known| *known == code) {
+            Some(index) => codes[(index + 1) % codes.len()].to_string(),
+            None => codes[0].to_string(),
+        }
+    }
+
+    pub fn next_from(&mut self) {
+        self.from = self.next(&self.from);
+    }
+
+    pub fn next_to(&mut self) {
+        self.to = self.next(&self.to);
+    }
+
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.from, &mut self.to);
+    }
+
+    pub fn convert(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+        // A cross rate through the base: value / (from per base) * (to per base)
+        let working: u32 = (precision + GUARD_DIGITS).min(MAX_PRECISION);
+        let from: Decimal = self.table.per_base(&self.from)?;
+        let to: Decimal = self.table.per_base(&self.to)?;
+        value.checked_div(from, working)?.checked_mul(to, working)?.round(precision)
+    }
+
+    pub fn label(&self) -> String { // Shown on the device screen, such as "USD>EUR"
+        format!("{}>{}", self.from, self.to)
+    }
+}
+
+impl Default for CurrencyConverter {
+    fn default() -> Self { // "USD>EUR" with no rates, so converting shows the missing rate error until a table loads
+        CurrencyConverter {
+            table: RateTable::default(),
+            from: "USD".to_string(),
+            to: "EUR".to_string(),
+        }
+    }
+}
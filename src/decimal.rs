@@ -0,0 +1,418 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::CalcError;
+
+/// Default number of fractional digits kept after a multiply or divide.
+pub const DEFAULT_PRECISION: u32 = 12;
+
+/// Most fractional digits any `Decimal` may carry, whatever the precision is set to.
+pub const MAX_PRECISION: u32 = 28;
+
+/// Extra fractional digits carried through a multi-step calculation, such as a
+/// conversion through a base unit, so the answer still rounds cleanly to the precision asked for.
+pub const GUARD_DIGITS: u32 = 6;
+
+/// Fewest significant digits a rounded fixed-point display may keep before
+/// `Decimal::to_width` switches to scientific notation.
+const MIN_SIGNIFICANT: u32 = 3;
+
+/// An exact base-10 number: `mantissa * 10^-scale`.
+///
+/// Values are always kept normalized (no trailing fractional zeros), so two
+/// equal numbers always compare equal field-by-field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal { mantissa: 0, scale: 0 };
+    pub const ONE: Decimal = Decimal { mantissa: 1, scale: 0 };
+
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        let mut res = Decimal { mantissa, scale };
+        res.normalize();
+        res
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn from_f64(input: f64) -> Result<Self, CalcError> {
+        if input.is_nan() {
+            return Err(CalcError::Domain);
+        }
+        if input.is_infinite() {
+            return Err(CalcError::Overflow);
+        }
+        if input.abs() < 0.5e-28 {
+            return Ok(Decimal::ZERO); // Below the finest step MAX_PRECISION can hold
+        }
+        // f64's Display is the shortest string that round-trips, so 0.1 stays 0.1
+        input.to_string().parse::<Decimal>().map_err(|_| CalcError::Overflow)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse::<f64>().unwrap_or(0.0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    pub fn checked_neg(&self) -> Result<Self, CalcError> { // i128::MIN has no positive twin
+        Ok(Decimal { mantissa: self.mantissa.checked_neg().ok_or(CalcError::Overflow)?, scale: self.scale })
+    }
+
+    pub fn checked_abs(&self) -> Result<Self, CalcError> {
+        Ok(Decimal { mantissa: self.mantissa.checked_abs().ok_or(CalcError::Overflow)?, scale: self.scale })
+    }
+
+    pub fn checked_add(&self, rhs: Decimal) -> Result<Self, CalcError> {
+        let (lhs, rhs, scale) = Self::align(*self, rhs)?;
+        Ok(Decimal::new(lhs.checked_add(rhs).ok_or(CalcError::Overflow)?, scale))
+    }
+
+    pub fn checked_sub(&self, rhs: Decimal) -> Result<Self, CalcError> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, rhs: Decimal, precision: u32) -> Result<Self, CalcError> {
+        let mantissa = self.mantissa.checked_mul(rhs.mantissa).ok_or(CalcError::Overflow)?;
+        let product: Decimal = Decimal::new(mantissa, self.scale + rhs.scale);
+        product.round(working(precision, product.magnitude()))
+    }
+
+    pub fn checked_div(&self, rhs: Decimal, precision: u32) -> Result<Self, CalcError> {
+        if rhs.is_zero() {
+            return Err(CalcError::DivideByZero);
+        }
+        let precision = working(precision, self.magnitude() - rhs.magnitude()).min(MAX_PRECISION);
+        // a / b = (am * 10^(bs + p - as)) / bm, computed at scale p
+        let shift = rhs.scale as i64 + precision as i64 - self.scale as i64;
+        let (num, den) = if shift >= 0 {
+            (self.mantissa.unsigned_abs(), rhs.mantissa.unsigned_abs())
+        } else {
+            match rhs.mantissa.checked_mul(pow10((-shift) as u32)?) {
+                Some(den) => (self.mantissa.unsigned_abs(), den.unsigned_abs()),
+                None => return Ok(Decimal::ZERO), // The divisor outgrows any mantissa, so the quotient is under half a unit
+            }
+        };
+        let quotient: u128 = long_div(num, den, shift.max(0) as u32).ok_or(CalcError::Overflow)?;
+        let quotient: i128 = i128::try_from(quotient).map_err(|_| CalcError::Overflow)?;
+        let negative: bool = (self.mantissa < 0) != (rhs.mantissa < 0);
+        Ok(Decimal::new(if negative { -quotient } else { quotient }, precision))
+    }
+
+    /// Remainder of truncating division, taking the sign of `self`.
+    pub fn checked_rem(&self, rhs: Decimal) -> Result<Self, CalcError> {
+        let (lhs, rhs, scale) = Self::align(*self, rhs)?;
+        if rhs == 0 {
+            return Err(CalcError::DivideByZero);
+        }
+        Ok(Decimal::new(lhs.checked_rem(rhs).ok_or(CalcError::Overflow)?, scale))
+    }
+
+    /// Drops the fractional digits, rounding toward zero.
+    pub fn trunc(&self) -> Self {
+        match pow10(self.scale) {
+            Ok(divisor) => Decimal::new(self.mantissa / divisor, 0),
+            Err(_) => Decimal::ZERO, // Every digit is fractional
+        }
+    }
+
+    /// The value as a whole number, a fraction is a Domain error.
+    pub fn to_integer(&self) -> Result<i128, CalcError> {
+        if self.scale > 0 {
+            return Err(CalcError::Domain);
+        }
+        Ok(self.mantissa)
+    }
+
+    /// Raises to `exp`, exactly for whole exponents and through f64 otherwise.
+    pub fn checked_pow(&self, exp: Decimal, precision: u32) -> Result<Self, CalcError> {
+        if self.is_zero() && exp.is_negative() {
+            return Err(CalcError::DivideByZero);
+        }
+        if exp.scale == 0 {
+            match self.powi(exp.mantissa.unsigned_abs(), precision) {
+                Ok(res) if exp.is_negative() => return Decimal::ONE.checked_div(res, precision),
+                Ok(res) => return res.round(precision),
+                Err(CalcError::Overflow) => {}, // Guard digits ran out, f64 decides if the result fits
+                Err(error) => return Err(error),
+            }
+        } else if self.is_negative() {
+            return Err(CalcError::Domain); // No real root
+        }
+        Decimal::from_f64(self.to_f64().powf(exp.to_f64()))?.round(precision)
+    }
+
+    fn powi(&self, mut exp: u128, precision: u32) -> Result<Self, CalcError> {
+        let guard: u32 = (precision + 4).min(MAX_PRECISION); // Keeps rounding in the squares out of the result
+        let mut res: Decimal = Decimal::ONE;
+        let mut base: Decimal = *self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = res.checked_mul(base, guard)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(base, guard)?;
+            }
+        }
+        Ok(res)
+    }
+
+    /// Rounds half away from zero to at most `precision` fractional digits.
+    pub fn round(&self, precision: u32) -> Result<Self, CalcError> {
+        if self.scale <= precision {
+            return Ok(*self);
+        }
+        let divisor = match pow10(self.scale - precision) {
+            Ok(divisor) => divisor,
+            Err(_) => return Ok(Decimal::ZERO), // Past 10^38 every mantissa is under half a unit
+        };
+        Ok(Decimal::new(div_round(self.mantissa, divisor), precision))
+    }
+
+    /// Formats into at most `width` characters, dropping fractional digits
+    /// as needed. A number whose whole part does not fit, or that would keep
+    /// too few significant digits, goes to scientific notation such as "2.998e8".
+    /// Fails only when that does not fit either.
+    pub fn to_width(&self, width: usize) -> Result<String, CalcError> {
+        let mut precision: u32 = self.scale;
+        loop {
+            let rounded: Decimal = self.round(precision)?;
+            let text = rounded.to_string();
+            if text.len() <= width {
+                if rounded == *self || rounded.digits() >= MIN_SIGNIFICANT {
+                    return Ok(text);
+                }
+                break;
+            }
+            if precision == 0 {
+                break;
+            }
+            precision -= 1;
+        }
+        self.to_scientific(width)
+    }
+
+    /// Formats as "d.ddde-x" with as many digits as fit in `width` characters.
+    pub fn to_scientific(&self, width: usize) -> Result<String, CalcError> {
+        if self.is_zero() {
+            return Ok("0".to_string());
+        }
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits: String = self.mantissa.unsigned_abs().to_string();
+        for kept in (1..=digits.len()).rev() {
+            let (lead, carried) = round_digits(&digits, kept);
+            let exponent: i64 = self.magnitude() - 1 + carried as i64;
+            let fraction: &str = lead[1..].trim_end_matches('0');
+            let text = if fraction.is_empty() {
+                format!("{}{}e{}", sign, &lead[..1], exponent)
+            } else {
+                format!("{}{}.{}e{}", sign, &lead[..1], fraction, exponent)
+            };
+            if text.len() <= width {
+                return Ok(text);
+            }
+        }
+        Err(CalcError::Overflow)
+    }
+
+    /// Digits before the point, or minus the zeros between the point and the
+    /// first significant digit for a value below 1.
+    fn magnitude(&self) -> i64 {
+        self.digits() as i64 - self.scale as i64
+    }
+
+    fn digits(&self) -> u32 { // Significant digits in the mantissa, none for zero
+        self.mantissa.unsigned_abs().checked_ilog10().map_or(0, |log| log + 1)
+    }
+
+    fn normalize(&mut self) {
+        while self.scale > 0 && self.mantissa % 10 == 0 {
+            self.mantissa /= 10;
+            self.scale -= 1;
+        }
+        if self.mantissa == 0 {
+            self.scale = 0;
+        }
+    }
+
+    fn align(lhs: Decimal, rhs: Decimal) -> Result<(i128, i128, u32), CalcError> {
+        let scale = lhs.scale.max(rhs.scale);
+        let lhs_m = lhs.mantissa.checked_mul(pow10(scale - lhs.scale)?).ok_or(CalcError::Overflow)?;
+        let rhs_m = rhs.mantissa.checked_mul(pow10(scale - rhs.scale)?).ok_or(CalcError::Overflow)?;
+        Ok((lhs_m, rhs_m, scale))
+    }
+}
+
+fn pow10(exp: u32) -> Result<i128, CalcError> {
+    10i128.checked_pow(exp).ok_or(CalcError::Overflow)
+}
+
+/// The first `kept` of `digits` rounded half up, and whether rounding carried
+/// into a new leading digit, as 999 kept to two gives "10" and a carry.
+fn round_digits(digits: &str, kept: usize) -> (String, bool) {
+    let lead: u128 = digits[..kept].parse().unwrap_or(0);
+    let up: bool = digits[kept..].starts_with(['5', '6', '7', '8', '9']);
+    let lead: String = (lead + up as u128).to_string();
+    if lead.len() > kept {
+        (lead[..kept].to_string(), true)
+    } else {
+        (lead, false)
+    }
+}
+
+/// `precision` fractional digits, unless a value of the given magnitude would
+/// round to 0 there, then up to MAX_PRECISION so it keeps `precision` significant digits.
+fn working(precision: u32, magnitude: i64) -> u32 {
+    let zeros: u32 = (-magnitude).clamp(0, MAX_PRECISION as i64) as u32;
+    if zeros < precision {
+        return precision;
+    }
+    precision.max((precision + zeros).min(MAX_PRECISION))
+}
+
+/// `num * 10^shift / den` rounded half away from zero, one digit at a time so
+/// the shifted numerator never has to fit.
+fn long_div(num: u128, den: u128, shift: u32) -> Option<u128> {
+    let mut quotient: u128 = num / den;
+    let mut remainder: u128 = num % den;
+    for _ in 0..shift {
+        let carried: u128 = remainder.checked_mul(10)?;
+        quotient = quotient.checked_mul(10)?.checked_add(carried / den)?;
+        remainder = carried % den;
+    }
+    if remainder >= den - remainder {
+        quotient = quotient.checked_add(1)?;
+    }
+    Some(quotient)
+}
+
+fn div_round(num: i128, den: i128) -> i128 {
+    let quotient = num / den;
+    let remainder = num % den;
+    if remainder.unsigned_abs() >= den.unsigned_abs() - remainder.unsigned_abs() {
+        if (num < 0) != (den < 0) {
+            quotient - 1
+        } else {
+            quotient + 1
+        }
+    } else {
+        quotient
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Decimal::ZERO
+    }
+}
+
+impl From<i32> for Decimal {
+    fn from(input: i32) -> Self {
+        Decimal::new(input as i128, 0)
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(input: i64) -> Self {
+        Decimal::new(input as i128, 0)
+    }
+}
+
+impl From<usize> for Decimal {
+    fn from(input: usize) -> Self {
+        Decimal::new(input as i128, 0)
+    }
+}
+
+impl From<u32> for Decimal {
+    fn from(input: u32) -> Self {
+        Decimal::new(input as i128, 0)
+    }
+}
+
+impl PartialEq<f64> for Decimal {
+    fn eq(&self, other: &f64) -> bool {
+        Decimal::from_f64(*other) == Ok(*self)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match Self::align(*self, *other) {
+            Ok((lhs, rhs, _)) => lhs.cmp(&rhs),
+            Err(_) => self.to_f64().partial_cmp(&other.to_f64()).unwrap_or(Ordering::Equal), // Only when aligning overflows
+        }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = CalcError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (digits, ""),
+        };
+        if whole.len() + fraction.len() == 0 {
+            return Err(CalcError::InvalidInput);
+        }
+        let mut mantissa: i128 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            let digit = c.to_digit(10).ok_or(CalcError::InvalidInput)?;
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit as i128))
+                .ok_or(CalcError::Overflow)?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+        Ok(Decimal::new(mantissa, fraction.len() as u32))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let scale = self.scale as usize;
+        let padded = if digits.len() <= scale {
+            "0".repeat(scale - digits.len() + 1) + &digits
+        } else {
+            digits
+        };
+        let (whole, fraction) = padded.split_at(padded.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::decimal::{Decimal, DEFAULT_PRECISION};
+use crate::{CalcError, PendingOp};
+
+/// Evaluates an infix string such as "(2+3)*4/7" with standard precedence.
+pub fn evaluate(input: &str) -> Result<Decimal, CalcError> {
+    evaluate_with_precision(input, DEFAULT_PRECISION)
+}
+
+pub fn evaluate_with_precision(input: &str, precision: u32) -> Result<Decimal, CalcError> {
+    let tokens = tokenize(input)?;
+    parse(&tokens)?.eval(precision)
+}
+
+/// Numbers are decimals when read from a string, the engine fills in its own `Number`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Token<T = Decimal> {
+    Number(T),
+    Op(PendingOp),
+    LParen,
+    RParen,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr<T = Decimal> {
+    Number(T),
+    Neg(Box<Expr<T>>),
+    Binary(PendingOp, Box<Expr<T>>, Box<Expr<T>>),
+}
+
+impl Expr {
+    pub fn eval(&self, precision: u32) -> Result<Decimal, CalcError> {
+        self.eval_with(&|op: PendingOp, lhs, rhs| op.apply(lhs, rhs, precision))
+    }
+}
+
+/// Negation that reports overflow, for the unary minus in an expression.
+pub trait CheckedNeg: Sized {
+    fn checked_neg(&self) -> Result<Self, CalcError>;
+}
+
+impl CheckedNeg for Decimal {
+    fn checked_neg(&self) -> Result<Self, CalcError> {
+        Decimal::checked_neg(self)
+    }
+}
+
+impl<T: Copy + CheckedNeg> Expr<T> {
+    /// Evaluates with the caller's arithmetic, such as programmer mode's wrapping integers.
+    pub fn eval_with<F>(&self, apply: &F) -> Result<T, CalcError>
+    where
+        F: Fn(PendingOp, T, T) -> Result<T, CalcError>,
+    {
+        match self {
+            Expr::Number(value) => Ok(*value),
+            Expr::Neg(inner) => inner.eval_with(apply)?.checked_neg(),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.eval_with(apply)?;
+                let rhs = rhs.eval_with(apply)?;
+                apply(*op, lhs, rhs)
+            },
+        }
+    }
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            },
+            '0'..='9' | '.' => {
+                let mut num: String = "".to_string();
+                while let Some(&d) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' {
+                        num.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let value = num.parse::<Decimal>()?;
+                tokens.push(Token::Number(value));
+            },
+            _ => {
+                let token = match c {
+                    '+' => Token::Op(PendingOp::Add),
+                    '-' => Token::Op(PendingOp::Subtract),
+                    '*' | '×' => Token::Op(PendingOp::Multiply),
+                    '/' | '÷' => Token::Op(PendingOp::Divide),
+                    '^' => Token::Op(PendingOp::Power),
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => return Err(CalcError::InvalidInput),
+                };
+                tokens.push(token);
+                chars.next();
+            },
+        }
+    }
+    Ok(tokens)
+}
+
+pub fn parse<T: Copy>(tokens: &[Token<T>]) -> Result<Expr<T>, CalcError> {
+    if tokens.is_empty() {
+        return Err(CalcError::InvalidInput);
+    }
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let expr = parser.parse_binary(1)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(_) => Err(CalcError::InvalidInput), // Leftover tokens, such as an unmatched ")"
+    }
+}
+
+/// Deepest the parser goes into brackets, signs and right-hand powers
+/// before giving up, so a long input cannot run it out of stack.
+const MAX_NESTING: usize = 64;
+
+struct Parser<'a, T> {
+    tokens: &'a [Token<T>],
+    pos: usize,
+    depth: usize, // Every level of nesting passes through parse_binary
+}
+
+impl<'a, T: Copy> Parser<'a, T> {
+    fn peek(&self) -> Option<Token<T>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<T>> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    // Precedence climbing: only binds operators at or above `min_prec`
+    fn parse_binary(&mut self, min_prec: u32) -> Result<Expr<T>, CalcError> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err(CalcError::InvalidInput);
+        }
+        let mut lhs = self.parse_unary()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let prec = op.precedence();
+            if prec < min_prec {
+                break;
+            }
+            self.next();
+            let next_min = if op.right_associative() { prec } else { prec + 1 };
+            let rhs = self.parse_binary(next_min)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        self.depth -= 1;
+        Ok(lhs)
+    }
+
+    // A sign binds looser than ^, so -2^2 is -(2^2)
+    fn parse_unary(&mut self) -> Result<Expr<T>, CalcError> {
+        match self.peek() {
+            Some(Token::Op(PendingOp::Subtract)) => {
+                self.next();
+                Ok(Expr::Neg(Box::new(self.parse_binary(PendingOp::Power.precedence())?)))
+            },
+            Some(Token::Op(PendingOp::Add)) => {
+                self.next();
+                self.parse_binary(PendingOp::Power.precedence())
+            },
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr<T>, CalcError> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::LParen) => {
+                let inner = self.parse_binary(1)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err(CalcError::InvalidInput),
+                }
+            },
+            Some(Token::RParen) => Err(CalcError::InvalidInput),
+            _ => Err(CalcError::InvalidInput),
+        }
+    }
+}
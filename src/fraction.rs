@@ -0,0 +1,169 @@
+use std::fmt;
+
+use crate::decimal::Decimal;
+use crate::{CalcError, PendingOp};
+
+/// An exact rational `num / den`.
+///
+/// Always kept reduced with a positive denominator, so equal values compare
+/// equal field-by-field, the same as `Decimal`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    num: i128,
+    den: i128,
+}
+
+impl Fraction {
+    pub fn new(num: i128, den: i128) -> Result<Self, CalcError> {
+        if den == 0 {
+            return Err(CalcError::DivideByZero);
+        }
+        let divisor: i128 = gcd(num, den);
+        let (mut num, mut den) = (num / divisor, den / divisor);
+        if den < 0 {
+            num = num.checked_neg().ok_or(CalcError::Overflow)?;
+            den = den.checked_neg().ok_or(CalcError::Overflow)?;
+        }
+        Ok(Fraction { num, den })
+    }
+
+    pub fn from_decimal(value: Decimal) -> Result<Self, CalcError> { // Exact, 0.25 becomes 1/4
+        let den: i128 = 10i128.checked_pow(value.scale()).ok_or(CalcError::Overflow)?;
+        Fraction::new(value.mantissa(), den)
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn trunc(&self) -> i128 { // Whole part, rounding toward zero
+        self.num / self.den
+    }
+
+    pub fn checked_neg(&self) -> Result<Self, CalcError> { // i128::MIN has no positive twin
+        Ok(Fraction { num: self.num.checked_neg().ok_or(CalcError::Overflow)?, den: self.den })
+    }
+
+    pub fn to_decimal(&self, precision: u32) -> Result<Decimal, CalcError> {
+        Decimal::new(self.num, 0).checked_div(Decimal::new(self.den, 0), precision)
+    }
+
+    pub fn checked_add(&self, rhs: Fraction) -> Result<Self, CalcError> {
+        // Over the lowest common denominator, to put off overflow as long as possible
+        let divisor: i128 = gcd(self.den, rhs.den);
+        let lhs_scale: i128 = rhs.den / divisor;
+        let rhs_scale: i128 = self.den / divisor;
+        let num = self.num.checked_mul(lhs_scale)
+            .and_then(|lhs| rhs.num.checked_mul(rhs_scale).and_then(|rhs| lhs.checked_add(rhs)))
+            .ok_or(CalcError::Overflow)?;
+        let den = self.den.checked_mul(lhs_scale).ok_or(CalcError::Overflow)?;
+        Fraction::new(num, den)
+    }
+
+    pub fn checked_sub(&self, rhs: Fraction) -> Result<Self, CalcError> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, rhs: Fraction) -> Result<Self, CalcError> {
+        // Cross-reduce first so the products stay small
+        let a: i128 = gcd(self.num, rhs.den);
+        let b: i128 = gcd(rhs.num, self.den);
+        let num = (self.num / a).checked_mul(rhs.num / b).ok_or(CalcError::Overflow)?;
+        let den = (self.den / b).checked_mul(rhs.den / a).ok_or(CalcError::Overflow)?;
+        Fraction::new(num, den)
+    }
+
+    pub fn checked_div(&self, rhs: Fraction) -> Result<Self, CalcError> {
+        self.checked_mul(rhs.recip()?)
+    }
+
+    pub fn recip(&self) -> Result<Self, CalcError> {
+        Fraction::new(self.den, self.num)
+    }
+
+    pub fn checked_rem(&self, rhs: Fraction) -> Result<Self, CalcError> {
+        let quotient: Fraction = self.checked_div(rhs)?;
+        self.checked_sub(rhs.checked_mul(Fraction::new(quotient.trunc(), 1)?)?)
+    }
+
+    pub fn checked_powi(&self, exp: i128) -> Result<Self, CalcError> {
+        let power: u32 = u32::try_from(exp.unsigned_abs()).map_err(|_| CalcError::Overflow)?;
+        let num = self.num.checked_pow(power).ok_or(CalcError::Overflow)?;
+        let den = self.den.checked_pow(power).ok_or(CalcError::Overflow)?;
+        let res = Fraction::new(num, den)?;
+        if exp < 0 {
+            res.recip()
+        } else {
+            Ok(res)
+        }
+    }
+
+    pub fn sqrt(&self) -> Option<Self> { // Only when both parts are perfect squares
+        if self.num < 0 {
+            return None;
+        }
+        let num: i128 = isqrt(self.num)?;
+        let den: i128 = isqrt(self.den)?;
+        Fraction::new(num, den).ok()
+    }
+
+    /// The operators a fraction answers exactly, `None` for the rest.
+    pub fn apply(&self, op: PendingOp, rhs: Fraction) -> Option<Result<Self, CalcError>> {
+        match op {
+            PendingOp::Add => Some(self.checked_add(rhs)),
+            PendingOp::Subtract => Some(self.checked_sub(rhs)),
+            PendingOp::Multiply => Some(self.checked_mul(rhs)),
+            PendingOp::Divide => Some(self.checked_div(rhs)),
+            PendingOp::Modulo => Some(self.checked_rem(rhs)),
+            PendingOp::Power if rhs.is_integer() => Some(self.checked_powi(rhs.num)),
+            _ => None,
+        }
+    }
+
+    pub fn to_mixed(&self) -> String { // 5/3 shows as "1 2/3"
+        let whole: i128 = self.trunc();
+        if whole == 0 || self.is_integer() {
+            return self.to_string();
+        }
+        let rest: i128 = (self.num % self.den).abs();
+        format!("{} {}/{}", whole, rest, self.den)
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a == 0 { 1 } else { a as i128 }
+}
+
+fn isqrt(value: i128) -> Option<i128> {
+    let square = |root: i128| root.checked_mul(root).unwrap_or(i128::MAX);
+    let mut root: i128 = (value as f64).sqrt() as i128; // Close, then nudged onto the exact root
+    while square(root) > value {
+        root -= 1;
+    }
+    while square(root + 1) <= value {
+        root += 1;
+    }
+    if square(root) == value { Some(root) } else { None }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
@@ -5,15 +5,14 @@ use bevy_mod_raycast::prelude::*;
 
 use crate::calculator::{CalcButtons, MeshColor, ScreenAlbedoState};
 use crate::cam_world::CameraWorld;
-use crate::{sum_calc_operations};
-use crate::{OpIndex, SumCurrent, SumVariable};
+use crate::{CalcEngine, OpIndex};
 
 pub fn fire_ray(
     mut raycast: Raycast,
     camera_query: Query<(&Camera, &GlobalTransform), With<CameraWorld>>, // Only query for the CameraWorld    
     windows: Query<&Window>,
     interactable_query: Query<Entity, With<Interactable>>,
-    mut op_index: ResMut<OpIndex>,
+    op_index: Res<OpIndex>,
     mut screen_albedo: ResMut<ScreenAlbedoState>,
 ) {    
     let (camera, camera_transform) = match camera_query.get_single() {
@@ -39,14 +38,8 @@ pub fn fire_ray(
     for (entity, _intersection) in hits {
         if Some(interactable_query.get(*entity)).is_some() {
             let button_index = entity.index();
-            if let Some(button) = CalcButtons::from_index(&mut op_index, button_index) {
-                match button {
-                    CalcButtons::NoneButtonLightPanel => {
-                        screen_albedo.state = 2;
-                    },
-                    _ => {
-                    },
-                }
+            if let Some(CalcButtons::NoneButtonLightPanel) = CalcButtons::from_index(&op_index, button_index) {
+                screen_albedo.state = 2;
             } 
         }
     }
@@ -58,8 +51,7 @@ pub fn release_ray(
     camera_query: Query<(&Camera, &GlobalTransform), With<CameraWorld>>, // Only query for the CameraWorld    
     windows: Query<&Window>,
     interactable_query: Query<Entity, With<Interactable>>,
-    mut sum: ResMut<SumCurrent>,
-    mut var: ResMut<SumVariable>,
+    mut engine: ResMut<CalcEngine>,
     mut op_index: ResMut<OpIndex>,
     mut screen_albedo: ResMut<ScreenAlbedoState>,
     asset_server: Res<AssetServer>,
@@ -87,130 +79,8 @@ pub fn release_ray(
     for (entity, _intersection) in hits {
         if Some(interactable_query.get(*entity)).is_some() {
             let button_index = entity.index();
-            if let Some(button) = CalcButtons::from_index(&mut op_index, button_index) {
+            if let Some(button) = CalcButtons::from_index(&op_index, button_index) {
                 match button {
-                    CalcButtons::Clear => {
-                        op_index.index = 1;
-                        op_index.last_op = 0;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Decimal => {                        
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        if var.var.len() == 0 {
-                            var.push(0);
-                        }
-                        var.decimal();
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Add => {
-                        op_index.index = 2;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Subtract => {
-                        op_index.index = 3;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Multiply => {
-                        op_index.index = 4;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Divide => {
-                        op_index.index = 5;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Sum => {
-                        op_index.index = 6;
-                        sum_calc_operations(&mut op_index, &mut var, &mut sum);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num0 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(0);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num1 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(1);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num2 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(2);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num3 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(3);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num4 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(4);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num5 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(5);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num6 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(6);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num7 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(7);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num8 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(8);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
-                    CalcButtons::Num9 => {
-                        if op_index.index == 6 {
-                            var.clear();
-                            op_index.index = 0;
-                        }
-                        var.push(9);
-                        click_animation(&asset_server, &mut commands, *entity);
-                    },
                     CalcButtons::NoneButtonBody => {
                         body_animation(&asset_server, &mut commands, *entity);
                     },
@@ -226,12 +96,117 @@ pub fn release_ray(
                     CalcButtons::NoneButtonLightPanel => {
                         screen_albedo.state = 1;
                     },
+                    _ => press_key(&mut engine, button, Some(*entity), &asset_server, &mut commands),
                 }
             } 
         }
     }
 }
 
+/// Presses a key for the user, clicked or typed, and animates it when it has a mesh.
+/// While a program runs only R/S gets through, to stop it.
+pub fn press_key(
+    engine: &mut ResMut<CalcEngine>,
+    button: CalcButtons,
+    entity: Option<Entity>,
+    asset_server: &Res<AssetServer>,
+    commands: &mut Commands,
+) {
+    if engine.programs.running() && button != CalcButtons::RunStop {
+        return;
+    }
+    step_key(engine, button, entity, asset_server, commands);
+}
+
+fn step_key( // Unguarded, as the running program presses its own keys
+    engine: &mut ResMut<CalcEngine>,
+    button: CalcButtons,
+    entity: Option<Entity>,
+    asset_server: &Res<AssetServer>,
+    commands: &mut Commands,
+) {
+    engine.press(button);
+    if let Some(entity) = entity {
+        click_animation(asset_server, commands, entity);
+    }
+}
+
+const PROGRAM_STEP_SECONDS: f32 = 0.25; // Slow enough to watch the keys go down
+
+/// Presses the running program's keys one at a time, animating those on the keypad.
+pub fn run_program(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut engine: ResMut<CalcEngine>,
+    op_index: Res<OpIndex>,
+    key_query: Query<Entity, With<Handle<Mesh>>>,
+    mut waited: Local<f32>,
+    asset_server: Res<AssetServer>,
+) {
+    if !engine.programs.running() {
+        *waited = PROGRAM_STEP_SECONDS; // The first key goes straight away
+        return;
+    }
+    *waited += time.delta_seconds();
+    if *waited < PROGRAM_STEP_SECONDS {
+        return;
+    }
+    *waited = 0.0;
+    if let Some(button) = engine.next_program_key() {
+        let entity: Option<Entity> = button.to_index(&op_index)
+            .and_then(|index| key_query.iter().find(|entity| entity.index() == index));
+        button.button_info();
+        step_key(&mut engine, button, entity, &asset_server, &mut commands);
+    }
+}
+
+pub fn keyboard_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut engine: ResMut<CalcEngine>,
+    asset_server: Res<AssetServer>,
+) {
+    let ctrl: bool = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift: bool = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for key in keys.get_just_released() {
+        let button: Option<CalcButtons> = if ctrl {
+            CalcButtons::from_ctrl_key(key)
+        } else if shift && !engine.wants_register() { // The camera only zooms on Shift while the mouse moves, so typing leaves it be
+            CalcButtons::from_shift_key(key).or_else(|| CalcButtons::from_key(key))
+        } else if engine.wants_register() {
+            CalcButtons::from_register_key(key).or_else(|| CalcButtons::from_key(key))
+        } else {
+            CalcButtons::from_key(key)
+        };
+        if let Some(button) = button {
+            button.button_info();
+            press_key(&mut engine, button, None, &asset_server, &mut commands);
+        }
+    }
+}
+
+/// Flashes the screen and shakes the body once each time the engine latches an error.
+pub fn error_feedback(
+    mut commands: Commands,
+    engine: Res<CalcEngine>,
+    body_query: Query<Entity, (With<Interactable>, With<Loaded>)>,
+    mut screen_albedo: ResMut<ScreenAlbedoState>,
+    mut latched: Local<bool>,
+    asset_server: Res<AssetServer>,
+) {
+    if !engine.is_changed() {
+        return;
+    }
+    let has_error = engine.error.is_some();
+    if has_error && !*latched {
+        screen_albedo.state = 1;
+        for entity in body_query.iter() {
+            body_animation(&asset_server, &mut commands, entity);
+        }
+    }
+    *latched = has_error;
+}
+
 pub fn spawn_gltf(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -251,7 +226,7 @@ pub fn spawn_gltf(
     // Circular plane
     commands.spawn((
         PbrBundle {
-            mesh: meshes.add(Circle::new(2000.)).into(),
+            mesh: meshes.add(Circle::new(2000.)),
             material: materials.add(Color::srgb(0.1, 0.0, 0.1)),
             transform: Transform {
                 translation: Vec3::new(0.0, -0.65, 0.0),
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+use crate::expression::Token;
+use crate::number::Number;
+use crate::PendingOp;
+
+pub const HISTORY_LIMIT: usize = 100; // The oldest calculations drop off past this
+
+/// One finished calculation, as keyed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calculation {
+    pub tokens: Vec<Token<Number>>,
+    pub result: Number,
+    pub tape: String, // "2 + 3 × 4 = 20", written in the mode it was worked out in
+    pub timestamp: u64, // Seconds since the Unix epoch
+}
+
+impl Calculation {
+    pub fn new(tokens: Vec<Token<Number>>, result: Number, tape: String) -> Self {
+        let timestamp: u64 = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+        Calculation { tokens, result, tape, timestamp }
+    }
+
+    pub fn operands(&self) -> Vec<Number> {
+        self.tokens.iter().filter_map(|token| match token {
+            Token::Number(value) => Some(*value),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn operators(&self) -> Vec<PendingOp> {
+        self.tokens.iter().filter_map(|token| match token {
+            Token::Op(op) => Some(*op),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn time_label(&self) -> String { // "14:05:09 UTC", the std library has no time zones
+        let seconds: u64 = self.timestamp % 86_400;
+        format!("{:02}:{:02}:{:02} UTC", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+}
+
+/// Every calculation finished since the app started, oldest first.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct CalcHistory {
+    pub entries: VecDeque<Calculation>,
+    pub scroll: usize, // How many of the newest entries the panel has scrolled past
+}
+
+impl CalcHistory {
+    pub fn new() -> Self {
+        CalcHistory::default()
+    }
+
+    pub fn record(&mut self, calculation: Calculation) {
+        info!("History: {}", calculation.tape);
+        self.entries.push_back(calculation);
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+        self.scroll = 0; // Back to the newest
+    }
+
+    pub fn scroll_back(&mut self, rows: usize) { // Toward older entries, keeping a full panel
+        self.scroll = (self.scroll + 1).min(self.entries.len().saturating_sub(rows));
+    }
+
+    pub fn scroll_forward(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn visible(&self, row: usize) -> Option<usize> { // The entry a panel row shows, newest at the top
+        let back: usize = self.scroll + row;
+        if back < self.entries.len() {
+            Some(self.entries.len() - 1 - back)
+        } else {
+            None
+        }
+    }
+}
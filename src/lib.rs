@@ -1,84 +1,1648 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)] // Bevy systems take their params by value
+
 use bevy::prelude::*;
 
+pub mod business;
 pub mod calculator;
 pub mod cam_ui;
 pub mod cam_world;
 pub mod cam_calc_screen;
+pub mod complex;
+pub mod constants;
+pub mod csv;
+pub mod currency;
+pub mod decimal;
+pub mod expression;
+pub mod fraction;
 pub mod game_env;
+pub mod history;
+pub mod number;
+pub mod program;
+pub mod programmer;
+pub mod screen;
+pub mod stats;
+pub mod tvm;
+pub mod undo;
+pub mod units;
+
+use crate::business::{Business, MarginVar};
+use crate::calculator::CalcButtons;
+use crate::complex::Complex;
+use crate::constants::ConstantPicker;
+use crate::currency::CurrencyConverter;
+use crate::decimal::{Decimal, DEFAULT_PRECISION, MAX_PRECISION};
+use crate::expression::{CheckedNeg, Token};
+use crate::history::{CalcHistory, Calculation};
+use crate::number::Number;
+use crate::program::{ProgramWait, Programs, Step};
+use crate::programmer::IntFormat;
+use crate::screen::{fit_digits, fit_line, fit_value, Screen, INPUT_WIDTH, PART_WIDTH, STACK_WIDTH, SUM_WIDTH};
+use crate::stats::{Statistic, Stats};
+use crate::tvm::{Tvm, TvmVar};
+use crate::undo::{Snapshot, UndoStack};
+use crate::units::Converter;
 
 pub fn sum_calc_operations(
-    op: &mut ResMut<OpIndex>,
-    var: &mut ResMut<SumVariable>,
-    sum: &mut ResMut<SumCurrent>,
+    engine: &mut CalcEngine,
+    call: CalcOperations,
 ) {
-    if let Some(call) = CalcOperations::from_index(op.index) {
-        match call {
-            CalcOperations::Init => {
-                SumCurrent::update_sum(var, sum, op);
+    match call {
+        CalcOperations::Init => {
+            engine.fold_entry();
+        },
+        CalcOperations::Clear => {
+            engine.error = None; // The only way out of an error
+            engine.shift = false;
+            engine.sum.zero();
+            engine.var.clear();
+            engine.last_op = None;
+            engine.expression.clear();
+            engine.paren_stack.clear();
+            engine.repeat = None;
+            engine.stack = [Number::ZERO; 3];
+            engine.last_x = Number::ZERO;
+            engine.stack_lift = true;
+            engine.tape.clear();
+            engine.stats.review = None; // The samples themselves are only cleared by CLΣ
+            engine.stats.pending_x = None;
+        },
+        CalcOperations::Add => {
+            engine.operator(PendingOp::Add);
+        },
+        CalcOperations::Subtract => {
+            engine.operator(PendingOp::Subtract);
+        },
+        CalcOperations::Multiply => {
+            engine.operator(PendingOp::Multiply);
+        },
+        CalcOperations::Divide => {
+            engine.operator(PendingOp::Divide);
+        },
+        CalcOperations::Power => {
+            engine.operator(PendingOp::Power);
+        },
+        CalcOperations::Markup => {
+            engine.operator(PendingOp::Markup);
+        },
+        CalcOperations::PercentChange => {
+            engine.operator(PendingOp::PercentChange);
+        },
+        CalcOperations::Percent => {
+            engine.percent();
+        },
+        CalcOperations::TaxAdd => {
+            engine.tax(true);
+        },
+        CalcOperations::TaxRemove => {
+            engine.tax(false);
+        },
+        CalcOperations::Margin(var) => {
+            engine.margin_key(var);
+        },
+        CalcOperations::GrandTotal => {
+            engine.recall(Number::Real(engine.business.grand_total));
+        },
+        CalcOperations::Modulo => {
+            engine.operator(PendingOp::Modulo);
+        },
+        CalcOperations::And => {
+            engine.operator(PendingOp::And);
+        },
+        CalcOperations::Or => {
+            engine.operator(PendingOp::Or);
+        },
+        CalcOperations::Xor => {
+            engine.operator(PendingOp::Xor);
+        },
+        CalcOperations::ShiftLeft => {
+            engine.operator(PendingOp::ShiftLeft);
+        },
+        CalcOperations::ShiftRight => {
+            engine.operator(PendingOp::ShiftRight);
+        },
+        CalcOperations::Unary(op) => {
+            engine.unary(op);
+        },
+        CalcOperations::Convert => {
+            engine.convert();
+        },
+        CalcOperations::StatAdd => {
+            engine.stat_update(false);
+        },
+        CalcOperations::StatRemove => {
+            engine.stat_update(true);
+        },
+        CalcOperations::Tvm(var) => {
+            engine.tvm_key(var);
+        },
+        CalcOperations::Statistic(stat) => {
+            match engine.stats.get(stat, engine.sum.precision) {
+                Ok(value) => engine.show_result(Number::Real(value)),
+                Err(error) => engine.error = Some(error),
+            }
+        },
+        CalcOperations::ClearEntry if engine.entry_mode == EntryMode::Rpn => {
+            engine.var.clear(); // CLx, the next number typed replaces X
+            engine.sum.zero();
+            engine.stack_lift = false;
+        },
+        CalcOperations::ClearEntry => {
+            engine.var.clear(); // Sum, pending operator and open groups are kept
+        },
+        CalcOperations::MemoryClear => {
+            engine.memory = Number::ZERO;
+        },
+        CalcOperations::MemoryRecall => {
+            engine.recall(engine.memory);
+        },
+        CalcOperations::MemoryAdd => {
+            engine.memory_update(PendingOp::Add);
+        },
+        CalcOperations::MemorySubtract => {
+            engine.memory_update(PendingOp::Subtract);
+        },
+        CalcOperations::OpenParen => {
+            engine.open_paren();
+        },
+        CalcOperations::CloseParen => {
+            engine.close_paren();
+        },
+        CalcOperations::Sum => {
+            match engine.entry_mode {
+                EntryMode::Chain => {
+                    engine.chain_sum();
+                },
+                EntryMode::Algebraic => {
+                    engine.algebraic_sum();
+                },
+                EntryMode::Rpn => {
+                    engine.rpn_enter(); // The Sum key is ENTER
+                },
+            }
+            if engine.mode == CalcMode::Business && engine.entry_mode != EntryMode::Rpn {
+                engine.collect_grand_total();
+            }
+        },
+        CalcOperations::Swap => {
+            engine.rpn_fold();
+            std::mem::swap(&mut engine.sum.sum, &mut engine.stack[0]);
+            engine.stack_lift = true;
+        },
+        CalcOperations::RollDown => {
+            engine.rpn_fold();
+            let x: Number = engine.sum.sum;
+            engine.sum.sum = engine.stack[0];
+            engine.stack = [engine.stack[1], engine.stack[2], x];
+            engine.stack_lift = true;
+        },
+        CalcOperations::LastX => {
+            engine.rpn_fold();
+            if engine.stack_lift {
+                engine.lift_stack();
+            }
+            engine.sum.sum = engine.last_x;
+            engine.stack_lift = true;
+        },
+    }
+    engine.index = match call {
+        CalcOperations::MemoryRecall | CalcOperations::GrandTotal => CalcOperations::Init, // The recalled value is a typed operand
+        CalcOperations::MemoryAdd | CalcOperations::MemorySubtract => CalcOperations::Sum,
+        CalcOperations::MemoryClear | CalcOperations::ClearEntry => engine.index,
+        CalcOperations::Unary(_) | CalcOperations::Convert => engine.index, // Set by apply_function, depending on what it acted on
+        CalcOperations::Percent => engine.index, // Set by apply_function, or the Sum it finished with
+        CalcOperations::StatAdd | CalcOperations::StatRemove | CalcOperations::Statistic(_) => CalcOperations::Sum, // Shown as a result
+        CalcOperations::Tvm(_) | CalcOperations::TaxAdd | CalcOperations::TaxRemove | CalcOperations::Margin(_) => CalcOperations::Sum,
+        _ => call,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryMode {
+    Chain,     // Each operator applies the previous one immediately: 2+3*4 = 20
+    Algebraic, // Operators follow precedence: 2+3*4 = 14
+    Rpn,       // Operands first, on a 4-level stack: 2 ENTER 3 + = 5
+}
+
+impl EntryMode {
+    pub fn next(&self) -> Self {
+        match self {
+            EntryMode::Chain => EntryMode::Algebraic,
+            EntryMode::Algebraic => EntryMode::Rpn,
+            EntryMode::Rpn => EntryMode::Chain,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalcMode {
+    Standard,
+    Programmer, // Wrapping integers in the word size and base of `int_format`
+    Fraction,   // Exact fractions wherever the arithmetic allows
+    Complex,    // Complex numbers, entered with the i key
+    Convert,    // Decimal, with the units of `converter` on the 2nd layer
+    Currency,   // Decimal, with the currencies of `currency` on the 2nd layer
+    Stats,      // Decimal, with Σ+ and the statistics of `stats` on the 2nd layer
+    Finance,    // Decimal, with the time value of money keys of `tvm` on the 2nd layer
+    Business,   // Decimal, with tax, markup and the grand total of `business` on the 2nd layer
+}
+
+impl CalcMode {
+    pub fn next(&self) -> Self {
+        match self {
+            CalcMode::Standard => CalcMode::Programmer,
+            CalcMode::Programmer => CalcMode::Fraction,
+            CalcMode::Fraction => CalcMode::Complex,
+            CalcMode::Complex => CalcMode::Convert,
+            CalcMode::Convert => CalcMode::Currency,
+            CalcMode::Currency => CalcMode::Stats,
+            CalcMode::Stats => CalcMode::Finance,
+            CalcMode::Finance => CalcMode::Business,
+            CalcMode::Business => CalcMode::Standard,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AngleMode { // Unit trig functions read and return angles in
+    Degrees,
+    Radians,
+    Grads,
+}
+
+impl AngleMode {
+    pub fn next(&self) -> Self {
+        match self {
+            AngleMode::Degrees => AngleMode::Radians,
+            AngleMode::Radians => AngleMode::Grads,
+            AngleMode::Grads => AngleMode::Degrees,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AngleMode::Degrees => "DEG",
+            AngleMode::Radians => "RAD",
+            AngleMode::Grads => "GRAD",
+        }
+    }
+
+    fn radians(&self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Degrees => angle.to_radians(),
+            AngleMode::Radians => angle,
+            AngleMode::Grads => angle * std::f64::consts::PI / 200.0,
+        }
+    }
+
+    fn angle(&self, radians: f64) -> f64 {
+        match self {
+            AngleMode::Degrees => radians.to_degrees(),
+            AngleMode::Radians => radians,
+            AngleMode::Grads => radians * 200.0 / std::f64::consts::PI,
+        }
+    }
+}
+
+#[derive(Clone, Resource)]
+pub struct CalcEngine {
+    pub var: SumVariable,
+    pub sum: SumCurrent,
+    pub memory: Number,
+    pub index: CalcOperations,
+    pub last_op: Option<PendingOp>,
+    pub repeat: Option<(PendingOp, Number)>, // Operator and operand a further Sum reapplies
+    pub entry_mode: EntryMode,
+    pub expression: Vec<Token<Number>>, // Algebraic mode input awaiting Sum
+    pub paren_stack: Vec<(Number, Option<PendingOp>)>, // Chain mode sum and operator waiting on each open group
+    pub error: Option<CalcError>, // Latched until Clear
+    pub angle_mode: AngleMode,
+    pub shift: bool, // 2nd was pressed, the next keypad key takes its second function
+    pub mode: CalcMode,
+    pub int_format: IntFormat,
+    pub show_fraction: bool, // Fraction mode shows fractions, or their decimal value
+    pub polar: bool, // Complex mode shows magnitude and angle rather than real and imaginary parts
+    pub stack: [Number; 3], // RPN Y, Z and T registers, X is the sum
+    pub last_x: Number, // RPN X before the last operation
+    pub stack_lift: bool, // RPN: the next number pushes X up the stack, off after ENTER and CLx
+    pub converter: Converter, // Unit table and the units conversion mode converts between
+    pub currency: CurrencyConverter, // Rate table and the currencies currency mode converts between
+    pub stats: Stats, // Samples entered in statistics mode
+    pub tape: Vec<Token<Number>>, // Chain mode's calculation so far, as keyed, for the history
+    pub history: CalcHistory,
+    pub undo: UndoStack, // Ctrl+Z and Ctrl+Y
+    pub constants: ConstantPicker, // Constant table and the CONST list
+    pub notice: Option<String>, // Shown on the status line for a moment, such as the constant just inserted
+    pub registers: Registers, // STO and RCL, A to Z
+    pub programs: Programs, // Keystroke programs, recorded with PRGM and run with R/S
+    pub tvm: Tvm, // Finance mode's N, I/Y, PV, PMT and FV
+    pub business: Business, // Business mode's tax rate, grand total and margins
+}
+
+impl CalcEngine {
+    pub fn new() -> Self {
+        CalcEngine {
+            var: SumVariable::new(),
+            sum: SumCurrent::new(),
+            memory: Number::ZERO,
+            index: CalcOperations::Clear,
+            last_op: None,
+            repeat: None,
+            entry_mode: EntryMode::Chain,
+            expression: Vec::new(),
+            paren_stack: Vec::new(),
+            error: None,
+            angle_mode: AngleMode::Degrees,
+            shift: false,
+            mode: CalcMode::Standard,
+            int_format: IntFormat::new(),
+            show_fraction: true,
+            polar: false,
+            stack: [Number::ZERO; 3],
+            last_x: Number::ZERO,
+            stack_lift: true,
+            converter: Converter::default(), // Filled from assets/units.csv at startup
+            currency: CurrencyConverter::default(), // Filled from assets/rates.csv at startup
+            stats: Stats::new(),
+            tape: Vec::new(),
+            history: CalcHistory::new(),
+            undo: UndoStack::new(),
+            constants: ConstantPicker::default(), // Filled from assets/constants.csv at startup
+            notice: None,
+            registers: Registers::new(),
+            programs: Programs::new(), // Filled from user/programs.txt, or the assets defaults, at startup
+            tvm: Tvm::new(),
+            business: Business::new(),
+        }
+    }
+
+    pub fn with_precision(precision: u32) -> Self {
+        CalcEngine {
+            sum: SumCurrent::with_precision(precision),
+            ..Self::new()
+        }
+    }
+
+    pub fn press(&mut self, button: CalcButtons) -> DisplayState {
+        match button {
+            CalcButtons::Undo => self.undo(),
+            CalcButtons::Redo => self.redo(),
+            _ => self.undoable(|engine| engine.handle(button)),
+        }
+        self.display()
+    }
+
+    fn undoable<F: FnOnce(&mut Self)>(&mut self, change: F) { // Presses that change nothing are not worth an undo step
+        let before: Snapshot = Snapshot::take(self);
+        change(self);
+        self.check_screen();
+        if Snapshot::take(self) != before {
+            self.undo.push(before);
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.error.is_some() {
+            return; // Latched, only Clear gets out
+        }
+        match self.undo.undo(Snapshot::take(self)) {
+            Some(before) => before.restore(self),
+            None => info!("Undo: nothing to undo"),
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.undo.redo(Snapshot::take(self)) {
+            Some(after) => after.restore(self),
+            None => info!("Redo: nothing to redo"),
+        }
+    }
+
+    fn handle(&mut self, button: CalcButtons) {
+        if self.programs.recording || self.programs.waiting.is_some() {
+            self.program_key(button); // Recorded, not worked out
+            return;
+        }
+        if self.error.is_some() && !matches!(button, CalcButtons::Clear) {
+            return; // Latched, only Clear gets out
+        }
+        self.notice = None;
+        if self.constants.open {
+            self.pick_constant(button);
+            return;
+        }
+        if let Some(action) = self.registers.browse {
+            self.browse_registers(action, button);
+            return;
+        }
+        if self.tvm.amort.is_some() {
+            self.view_amort(button);
+            return;
+        }
+        let button: CalcButtons = match button.second(self.mode) {
+            Some(second) if self.shift => {
+                self.shift = false;
+                second
+            },
+            _ => button,
+        };
+        let handled: bool = match self.mode { // The keys a mode adds, which no other mode takes
+            CalcMode::Programmer => self.handle_programmer(button.clone()),
+            CalcMode::Stats => self.handle_stats(button.clone()),
+            CalcMode::Finance => self.handle_finance(button.clone()),
+            CalcMode::Business => self.handle_business(button.clone()),
+            _ => false,
+        };
+        if handled {
+            return;
+        }
+        if let Some(mode) = button.mode() {
+            info!("{:?}: only works in {:?} mode", button, mode);
+            return;
+        }
+        match button {
+            CalcButtons::Clear => sum_calc_operations(self, CalcOperations::Clear),
+            CalcButtons::Add => sum_calc_operations(self, CalcOperations::Add),
+            CalcButtons::Subtract => sum_calc_operations(self, CalcOperations::Subtract),
+            CalcButtons::Multiply => sum_calc_operations(self, CalcOperations::Multiply),
+            CalcButtons::Divide => sum_calc_operations(self, CalcOperations::Divide),
+            CalcButtons::Sum => sum_calc_operations(self, CalcOperations::Sum),
+            CalcButtons::Percent => sum_calc_operations(self, CalcOperations::Percent),
+            CalcButtons::ClearEntry => sum_calc_operations(self, CalcOperations::ClearEntry),
+            CalcButtons::MemoryClear => sum_calc_operations(self, CalcOperations::MemoryClear),
+            CalcButtons::MemoryRecall => sum_calc_operations(self, CalcOperations::MemoryRecall),
+            CalcButtons::MemoryAdd => sum_calc_operations(self, CalcOperations::MemoryAdd),
+            CalcButtons::MemorySubtract => sum_calc_operations(self, CalcOperations::MemorySubtract),
+            CalcButtons::OpenParen | CalcButtons::CloseParen if self.entry_mode == EntryMode::Rpn => {
+                info!("{:?}: RPN has no parentheses, the stack holds the operands", button);
+            },
+            CalcButtons::OpenParen => sum_calc_operations(self, CalcOperations::OpenParen),
+            CalcButtons::CloseParen => sum_calc_operations(self, CalcOperations::CloseParen),
+            CalcButtons::Swap => sum_calc_operations(self, CalcOperations::Swap),
+            CalcButtons::RollDown => sum_calc_operations(self, CalcOperations::RollDown),
+            CalcButtons::LastX => sum_calc_operations(self, CalcOperations::LastX),
+            CalcButtons::Power => sum_calc_operations(self, CalcOperations::Power),
+            CalcButtons::Modulo => sum_calc_operations(self, CalcOperations::Modulo),
+            CalcButtons::And => sum_calc_operations(self, CalcOperations::And),
+            CalcButtons::Or => sum_calc_operations(self, CalcOperations::Or),
+            CalcButtons::Xor => sum_calc_operations(self, CalcOperations::Xor),
+            CalcButtons::ShiftLeft => sum_calc_operations(self, CalcOperations::ShiftLeft),
+            CalcButtons::ShiftRight => sum_calc_operations(self, CalcOperations::ShiftRight),
+            CalcButtons::Not => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Not)),
+            CalcButtons::SquareRoot => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::SquareRoot)),
+            CalcButtons::Square => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Square)),
+            CalcButtons::Reciprocal => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Reciprocal)),
+            CalcButtons::Sin => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Sin)),
+            CalcButtons::Cos => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Cos)),
+            CalcButtons::Tan => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Tan)),
+            CalcButtons::ArcSin => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::ArcSin)),
+            CalcButtons::ArcCos => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::ArcCos)),
+            CalcButtons::ArcTan => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::ArcTan)),
+            CalcButtons::Log10 => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Log10)),
+            CalcButtons::Ln => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Ln)),
+            CalcButtons::Exp => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Exp)),
+            CalcButtons::Factorial => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Factorial)),
+            CalcButtons::Magnitude => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Magnitude)),
+            CalcButtons::Argument => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Argument)),
+            CalcButtons::Conjugate => sum_calc_operations(self, CalcOperations::Unary(UnaryOp::Conjugate)),
+            CalcButtons::Second => self.shift = !self.shift,
+            CalcButtons::AngleMode => {
+                self.angle_mode = self.angle_mode.next();
+                info!("Angle mode: {:?}", self.angle_mode);
+            },
+            CalcButtons::Mode => self.set_mode(self.mode.next()),
+            CalcButtons::FractionToggle => self.show_fraction = !self.show_fraction,
+            CalcButtons::PolarToggle => self.polar = !self.polar,
+            CalcButtons::UnitCategory if self.mode == CalcMode::Currency => {
+                info!("UnitCategory: currencies have no categories");
+            },
+            CalcButtons::UnitFrom if self.mode == CalcMode::Currency => self.currency.next_from(),
+            CalcButtons::UnitTo if self.mode == CalcMode::Currency => self.currency.next_to(),
+            CalcButtons::UnitSwap if self.mode == CalcMode::Currency => self.currency.swap(),
+            CalcButtons::UnitCategory => self.converter.next_category(),
+            CalcButtons::UnitFrom => self.converter.next_from(),
+            CalcButtons::UnitTo => self.converter.next_to(),
+            CalcButtons::UnitSwap => self.converter.swap(),
+            CalcButtons::Convert => sum_calc_operations(self, CalcOperations::Convert),
+            CalcButtons::Program => self.programs.waiting = Some(ProgramWait::Record),
+            CalcButtons::Execute => self.programs.waiting = Some(ProgramWait::Execute),
+            CalcButtons::RunStop => self.programs.run_stop(),
+            CalcButtons::SingleStep => self.programs.single_step(),
+            CalcButtons::Label | CalcButtons::Goto | CalcButtons::Test => {
+                info!("{:?}: only a program step, PRGM records one", button);
+            },
+            CalcButtons::Store => self.registers.browse = Some(RegisterAction::Store),
+            CalcButtons::Recall => self.registers.browse = Some(RegisterAction::Recall),
+            CalcButtons::Constant => {
+                if !self.constants.open() {
+                    info!("Constant: no constants loaded");
+                }
+            },
+            CalcButtons::Imaginary if self.mode == CalcMode::Complex => {
+                self.start_entry();
+                self.var.toggle_imaginary();
+            },
+            CalcButtons::Imaginary => {
+                info!("Imaginary: only complex mode takes complex numbers");
+            },
+            CalcButtons::EntryMode => self.set_entry_mode(self.entry_mode.next()),
+            CalcButtons::SignToggle => {
+                let toggled: Result<(), CalcError> = match (self.index, self.entry_mode) {
+                    (_, EntryMode::Rpn) if !self.has_entry() => self.negate_sum(), // CHS on X
+                    (CalcOperations::Sum, _) | (CalcOperations::CloseParen, EntryMode::Chain) => {
+                        self.negate_sum() // Negate the result on show
+                    },
+                    (CalcOperations::CloseParen, EntryMode::Algebraic) => {
+                        info!("SignToggle: a closed group cannot be negated in algebraic entry");
+                        Ok(())
+                    },
+                    (CalcOperations::Unary(_) | CalcOperations::Convert | CalcOperations::Percent, _) if !self.var.is_empty() => {
+                        self.var.toggle_sign() // Negate a function result without starting over
+                    },
+                    _ => {
+                        self.start_entry();
+                        self.var.toggle_sign()
+                    },
+                };
+                if let Err(error) = toggled {
+                    self.error = Some(error);
+                }
+            },
+            CalcButtons::Backspace => {
+                if self.index == CalcOperations::Init {
+                    self.var.backspace();
+                }
+            },
+            CalcButtons::Decimal => {
+                self.start_entry();
+                self.typed(|var| {
+                    if var.is_empty() {
+                        var.push(0);
+                    }
+                    var.decimal();
+                });
+            },
+            CalcButtons::NoneButtonBody
+            | CalcButtons::NoneButtonScreen
+            | CalcButtons::NoneButtonLightPanel => {}, // Handled by the scene, not the engine
+            _ => {
+                if let Some(digit) = button.digit() {
+                    match self.mode {
+                        CalcMode::Standard
+                        | CalcMode::Fraction
+                        | CalcMode::Complex
+                        | CalcMode::Convert
+                        | CalcMode::Currency
+                        | CalcMode::Stats
+                        | CalcMode::Finance
+                        | CalcMode::Business if digit < 10 => {
+                            self.start_entry();
+                            self.typed(|var| var.push(digit));
+                        },
+                        CalcMode::Programmer if digit < self.int_format.base.radix() as i32 => {
+                            self.start_entry();
+                            self.var.push_within(digit, self.int_format.max_digits());
+                        },
+                        _ => info!("{:?}: not a digit in this base", button),
+                    }
+                }
+            },
+        }
+    }
+
+    fn typed<F: FnOnce(&mut SumVariable)>(&mut self, key: F) { // A digit past what the screen shows is dropped, as on the hardware
+        let before: SumVariable = self.var.clone();
+        key(&mut self.var);
+        let width: usize = if self.entry_mode == EntryMode::Rpn { SUM_WIDTH } else { INPUT_WIDTH }; // RPN types into X
+        if self.var.held.is_none() && self.var.to_display().chars().count() > width {
+            self.var = before;
+        }
+    }
+
+    pub fn recall_history(&mut self, index: usize) -> DisplayState { // A result from the history, as the input
+        if self.error.is_none() {
+            if let Some(calculation) = self.history.entries.get(index) {
+                let result: Number = calculation.result;
+                self.undoable(|engine| engine.recall(result));
+            }
+        }
+        self.display()
+    }
+
+    pub fn display(&self) -> DisplayState {
+        DisplayState {
+            input: self.var.to_display(),
+            sum: self.format(self.sum.sum),
+            depth: self.depth(),
+            memory: !self.memory.is_zero(),
+            entry_mode: self.entry_mode,
+            error: self.error,
+            angle_mode: self.angle_mode,
+            shift: self.shift,
+            mode: self.mode,
+            int_format: self.int_format,
+            show_fraction: self.show_fraction,
+            polar: self.polar,
+            sum_parts: self.parts(self.sum.sum),
+            stack: self.stack_lines(),
+            units: match self.mode {
+                CalcMode::Currency => self.currency.label(),
+                _ => self.converter.label(),
+            },
+            stats: self.stats.label(),
+            review: if self.has_entry() { None } else { self.stats.reviewing() },
+            constant: match self.constants.current() {
+                Some(constant) if self.constants.open => Some(constant.name.clone()),
+                _ => self.notice.clone(),
+            },
+            picker: if self.constants.open { self.constants.line() } else { None },
+            browse: self.registers.browse,
+            register: self.registers.browse.map(|_| {
+                let selected: usize = self.registers.selected;
+                format!("{}: {}", Registers::name(selected), self.format(self.registers.values[selected]))
+            }),
+            program: self.programs.line(),
+            program_status: self.programs.status(),
+            tvm: self.tvm.label(),
+            business: self.business.label(),
+            grand_total: !self.business.grand_total.is_zero(),
+            amort: self.tvm.amort_line(self.sum.precision),
+        }
+    }
+
+    pub fn screen(&self) -> Screen { // The device screen's lines, for the renderers to copy
+        self.fit_screen(&self.display()).unwrap_or_else(Screen::error)
+    }
+
+    fn check_screen(&mut self) { // What the screen cannot fit is an overflow, latched like any other error
+        if let Err(error) = self.fit_screen(&self.display()) {
+            self.error = Some(error);
+        }
+    }
+
+    fn fit_screen(&self, display: &DisplayState) -> Result<Screen, CalcError> {
+        if let Some(error) = display.error {
+            return Ok(Screen::error(error));
+        }
+        let sum: String = if display.mode == CalcMode::Programmer {
+            "Sum: ".to_owned() + &fit_digits(&display.sum, SUM_WIDTH)
+        } else if let Some(stack) = &display.stack {
+            "X: ".to_owned() + &fit_value(&stack[0], SUM_WIDTH, display.mode)? // RPN shows X here, with Y, Z and T on the input line
+        } else if let Some((first, second)) = &display.sum_parts {
+            // Complex values take a line for each part
+            let labels = if display.polar { ("R: ", "A: ") } else { ("Re: ", "Im: ") };
+            let fit = |part: &String| part.parse::<Decimal>().and_then(|value| value.to_width(PART_WIDTH));
+            labels.0.to_owned() + &fit(first)? + "\n" + labels.1 + &fit(second)?
+        } else if display.sum.len() <= SUM_WIDTH {
+            "Sum: ".to_owned() + &display.sum // Fractions fit as they are, or fall back to decimal
+        } else {
+            "Sum: ".to_owned() + &self.sum.sum.to_decimal(self.sum.precision)?.to_width(SUM_WIDTH)? // Rounded, or in scientific notation, to fit
+        };
+        let browsed: Option<&String> = display.program.as_ref()
+            .or(display.amort.as_ref())
+            .or(display.register.as_ref())
+            .or(display.picker.as_ref())
+            .or(display.review.as_ref());
+        let input: String = if let Some(line) = browsed {
+            fit_line(line) // A program step, or a schedule, register, constant or sample being browsed, takes the input line
+        } else if let Some(stack) = &display.stack {
+            let lines: Vec<String> = stack[1..].iter().map(|value| fit_value(value, STACK_WIDTH, display.mode)).collect::<Result<_, _>>()?;
+            format!("T: {}\nZ: {}\nY: {}", lines[2], lines[1], lines[0])
+        } else if display.mode == CalcMode::Programmer {
+            "Input: ".to_owned() + &fit_digits(&display.input, INPUT_WIDTH)
+        } else if (display.mode == CalcMode::Complex && self.var.held.is_some()) || matches!(self.var.held, Some(Number::Real(_))) {
+            // Held whole, only the screen cuts it short
+            let fitted: String = display.input.parse::<Decimal>()
+                .and_then(|value| value.to_width(INPUT_WIDTH))
+                .unwrap_or_else(|_| display.input.chars().take(INPUT_WIDTH).collect());
+            "Input: ".to_owned() + &fitted
+        } else if display.input.chars().count() > INPUT_WIDTH {
+            return Err(CalcError::Overflow); // A loaded value too long to show
+        } else {
+            "Input: ".to_owned() + &display.input
+        };
+        Ok(Screen { input, sum, stacked: display.stack.is_some() })
+    }
+
+    fn format(&self, value: Number) -> String { // A value as the active mode shows it
+        match self.mode {
+            CalcMode::Programmer => match value.to_integer() {
+                Ok(int) => self.int_format.format(int),
+                Err(_) => value.to_string(),
+            },
+            CalcMode::Fraction if self.show_fraction => value.to_mixed(),
+            CalcMode::Complex if self.polar => match self.parts(value) {
+                Some((magnitude, angle)) => format!("{}∠{}", magnitude, angle),
+                None => value.to_string(),
+            },
+            CalcMode::Complex => value.to_string(),
+            _ => match value.to_decimal(self.sum.precision) {
+                Ok(decimal) => decimal.to_string(),
+                Err(_) => value.to_string(),
+            },
+        }
+    }
+
+    fn parts(&self, value: Number) -> Option<(String, String)> { // The two lines complex mode shows a value on
+        if self.mode != CalcMode::Complex {
+            return None;
+        }
+        let value: Complex = value.to_complex(self.sum.precision).ok()?;
+        if self.polar {
+            let (magnitude, angle) = value.to_polar(self.sum.precision, self.angle_mode).ok()?;
+            Some((magnitude.to_string(), angle.to_string()))
+        } else {
+            Some((value.re.to_string(), value.im.to_string()))
+        }
+    }
+
+    fn stack_lines(&self) -> Option<[String; 4]> { // X, Y, Z and T as shown in RPN, X being the entry while one is typed
+        if self.entry_mode != EntryMode::Rpn {
+            return None;
+        }
+        let x: String = if self.has_entry() { self.var.to_display() } else { self.format(self.sum.sum) };
+        Some([x, self.format(self.stack[0]), self.format(self.stack[1]), self.format(self.stack[2])])
+    }
+
+    pub fn depth(&self) -> usize { // How many parentheses are still open
+        match self.entry_mode {
+            EntryMode::Rpn => 0,
+            EntryMode::Chain => self.paren_stack.len(),
+            EntryMode::Algebraic => {
+                let mut depth: usize = 0;
+                for token in &self.expression {
+                    match token {
+                        Token::LParen => depth += 1,
+                        Token::RParen => depth = depth.saturating_sub(1),
+                        _ => {},
+                    }
+                }
+                depth
             },
-            CalcOperations::Clear => {
-                SumCurrent::zero(sum);
-                var.clear();
+        }
+    }
+
+    fn negate_sum(&mut self) -> Result<(), CalcError> {
+        self.sum.sum = self.wrap(self.sum.sum.checked_neg()?);
+        Ok(())
+    }
+
+    fn has_entry(&self) -> bool { // Digits have been typed, or a function applied to them, since the last operation
+        matches!(self.index, CalcOperations::Init | CalcOperations::Unary(_) | CalcOperations::Convert | CalcOperations::Percent) && !self.var.is_empty()
+    }
+
+    fn operand_pending(&self) -> bool { // An operator or open group is waiting on its operand
+        match self.entry_mode {
+            EntryMode::Chain => self.last_op.is_some(),
+            EntryMode::Rpn => false,
+            EntryMode::Algebraic => matches!(self.expression.last(), Some(Token::Op(_)) | Some(Token::LParen)),
+        }
+    }
+
+    fn start_entry(&mut self) {
+        // A digit after Sum or a function starts a fresh input rather than extending the last operand
+        if self.index != CalcOperations::Init {
+            if matches!(self.index, CalcOperations::Sum | CalcOperations::Unary(_) | CalcOperations::Convert | CalcOperations::Percent) {
+                self.var.clear();
+            }
+            if self.entry_mode == EntryMode::Rpn && self.stack_lift {
+                self.lift_stack();
+            }
+            self.index = CalcOperations::Init;
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: CalcMode) {
+        // The sum carries across, anything half-entered does not
+        let sum: Number = self.sum.sum;
+        sum_calc_operations(self, CalcOperations::Clear);
+        self.mode = mode;
+        self.sum.sum = match (mode, sum.to_decimal(self.sum.precision)) {
+            (CalcMode::Complex, Err(_)) if matches!(sum, Number::Complex(_)) => sum,
+            (_, Err(_)) => Number::ZERO, // Including a complex sum, which no other mode can hold
+            (CalcMode::Programmer, Ok(value)) => self.wrap(Number::Real(value).trunc()),
+            (CalcMode::Fraction, Ok(value)) => Number::exact(value),
+            (CalcMode::Standard | CalcMode::Complex | CalcMode::Convert | CalcMode::Currency | CalcMode::Stats | CalcMode::Finance | CalcMode::Business, Ok(value)) => {
+                Number::Real(value)
             },
-            CalcOperations::Add => {
-                op.last_op = 1;
-                SumCurrent::var_to_sum_if_sum_zero(var, sum);
-                var.clear();
+        };
+        info!("Mode: {:?}", mode);
+    }
+
+    pub fn set_int_format(&mut self, format: IntFormat) {
+        // Values already stored are rewrapped, a typed entry is rewritten in the new base
+        let entry: Option<Number> = if self.has_entry() { Some(self.entry()) } else { None };
+        self.int_format = format;
+        if self.mode == CalcMode::Programmer {
+            self.sum.sum = self.wrap(self.sum.sum);
+            if let Some(value) = entry {
+                let index: CalcOperations = self.index;
+                self.load_entry(self.wrap(value));
+                self.index = index;
+            }
+        }
+        info!("Integer format: {}", format.label());
+    }
+
+    fn wrap(&self, value: Number) -> Number { // Integers in programmer mode keep to the word size
+        match (self.mode, value.to_integer()) {
+            (CalcMode::Programmer, Ok(int)) => Number::Real(Decimal::new(self.int_format.wrap(int), 0)),
+            _ => value,
+        }
+    }
+
+    fn entry(&self) -> Number { // The typed input as a value in the active mode
+        match self.mode {
+            CalcMode::Standard | CalcMode::Convert | CalcMode::Currency | CalcMode::Stats | CalcMode::Finance | CalcMode::Business => {
+                Number::Real(self.var.to_decimal())
             },
-            CalcOperations::Subtract => {
-                op.last_op = 2;
-                SumCurrent::var_to_sum_if_sum_zero(var, sum);
-                var.clear();
+            CalcMode::Programmer => {
+                let value: i128 = self.var.to_int(self.int_format.base.radix());
+                Number::Real(Decimal::new(self.int_format.wrap(value), 0))
             },
-            CalcOperations::Multiply => {
-                op.last_op = 3;
-                SumCurrent::var_to_sum_if_sum_zero(var, sum);
-                var.clear();
+            CalcMode::Fraction => match self.var.held {
+                Some(value) => value,
+                None => Number::exact(self.var.to_decimal()),
             },
-            CalcOperations::Divide => {
-                op.last_op = 4;
-                SumCurrent::var_to_sum_if_sum_zero(var, sum);
-                var.clear();
+            CalcMode::Complex => match self.var.held {
+                Some(value) => value,
+                None if self.var.imaginary => Number::complex(Complex::new(Decimal::ZERO, self.var.to_decimal())),
+                None => Number::Real(self.var.to_decimal()),
             },
-            CalcOperations::Sum => {
-                SumCurrent::update_sum(var, sum, op);
+        }
+    }
+
+    fn load_entry(&mut self, value: Number) { // Show a value as the input, as if it were typed
+        let loaded = match (self.mode, value) {
+            (CalcMode::Programmer, _) => {
+                let text: String = self.format(self.wrap(value.trunc()));
+                self.var.load_text(&text, self.int_format.max_digits())
+            },
+            (CalcMode::Fraction, Number::Ratio(_)) | (CalcMode::Complex, Number::Complex(_)) => {
+                self.var.load_held(value);
+                Ok(())
             },
+            _ => value.to_decimal(self.sum.precision).and_then(|decimal| self.var.load(decimal)),
+        };
+        if let Err(error) = loaded {
+            self.error = Some(error);
+        }
+    }
+
+    fn apply_op(&self, op: PendingOp, lhs: Number, rhs: Number) -> Result<Number, CalcError> {
+        match self.mode {
+            CalcMode::Programmer => {
+                let value: i128 = self.int_format.apply(op, lhs.to_integer()?, rhs.to_integer()?)?;
+                Ok(Number::Real(Decimal::new(value, 0)))
+            },
+            _ => Number::apply(op, lhs, rhs, self.sum.precision),
+        }
+    }
+
+    fn evaluate(&self) -> Result<Number, CalcError> { // The algebraic expression so far
+        expression::parse(&self.expression)?.eval_with(&|op, lhs, rhs| self.apply_op(op, lhs, rhs))
+    }
+
+    fn fold_entry(&mut self) { // Apply the waiting operator to the typed entry
+        if self.var.is_empty() {
+            return;
+        }
+        let entry: Number = self.entry();
+        let next = match self.last_op {
+            Some(op) => self.apply_op(op, self.sum.sum, entry),
+            None => Ok(entry),
+        };
+        match next {
+            Ok(value) => self.sum.sum = value,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub fn set_entry_mode(&mut self, mode: EntryMode) {
+        // Switching how operators bind would reinterpret what is half-entered, so start clean,
+        // keeping only the sum, which RPN takes up as X
+        let sum: Number = self.sum.sum;
+        sum_calc_operations(self, CalcOperations::Clear);
+        self.sum.sum = sum;
+        self.entry_mode = mode;
+        info!("Entry mode: {:?}", mode);
+    }
+
+    fn recall(&mut self, value: Number) { // A stored value becomes the input, as if it were typed
+        self.start_recall();
+        self.load_entry(value);
+    }
+
+    fn start_recall(&mut self) {
+        if self.entry_mode == EntryMode::Rpn && self.has_entry() {
+            self.rpn_fold(); // Ends the number being typed, so the recall lifts it
+            self.index = CalcOperations::Sum;
+            self.stack_lift = true;
+        }
+        self.start_entry(); // Lifts the RPN stack, as a typed number would
+    }
+
+    fn browse_registers(&mut self, action: RegisterAction, button: CalcButtons) { // The keypad picks a register while STO or RCL waits
+        let register: usize = match button {
+            CalcButtons::Add | CalcButtons::StatReviewPrev => {
+                self.registers.selected = (self.registers.selected + REGISTER_COUNT - 1) % REGISTER_COUNT;
+                return;
+            },
+            CalcButtons::Subtract | CalcButtons::StatReviewNext => {
+                self.registers.selected = (self.registers.selected + 1) % REGISTER_COUNT;
+                return;
+            },
+            CalcButtons::Clear | CalcButtons::ClearEntry => {
+                self.registers.browse = None;
+                return;
+            },
+            CalcButtons::Store => {
+                self.registers.browse = Some(RegisterAction::Store);
+                return;
+            },
+            CalcButtons::Recall => {
+                self.registers.browse = Some(RegisterAction::Recall);
+                return;
+            },
+            CalcButtons::Register(register) if register < REGISTER_COUNT => register,
+            CalcButtons::Sum => self.registers.selected,
+            _ => {
+                info!("{:?}: {} is waiting on a register, A to Z or = picks and AC cancels", button, action.label());
+                return;
+            },
+        };
+        self.registers.selected = register;
+        self.registers.browse = None;
+        match action {
+            RegisterAction::Store => {
+                self.registers.values[register] = self.showing();
+                if self.entry_mode == EntryMode::Rpn && self.has_entry() {
+                    self.rpn_fold(); // Ends the number, the next one typed lifts it
+                    self.index = CalcOperations::Sum;
+                    self.stack_lift = true;
+                }
+            },
+            RegisterAction::Recall => self.recall(self.registers.values[register]), // An operand, as if typed
+        }
+        info!("{} {}", action.label(), Registers::name(register));
+    }
+
+    fn program_key(&mut self, button: CalcButtons) { // Keys while recording, or while a programming key waits on its digit
+        if let Some(wait) = self.programs.waiting {
+            match button {
+                CalcButtons::Clear | CalcButtons::ClearEntry => self.programs.waiting = None,
+                _ => match button.digit() {
+                    Some(digit) if digit < 10 => self.programs.complete(wait, digit),
+                    _ => info!("{:?}: {} is waiting on a digit, AC cancels", button, wait.label()),
+                },
+            }
+            return;
+        }
+        match button {
+            CalcButtons::Program => {
+                self.programs.recording = false;
+                info!("Program {} recorded, {} steps", self.programs.selected, self.programs.slots[self.programs.selected].len());
+            },
+            CalcButtons::Backspace => self.programs.unrecord(),
+            CalcButtons::RunStop => self.programs.record(Step::Stop),
+            CalcButtons::Label => self.programs.waiting = Some(ProgramWait::Label),
+            CalcButtons::Goto => self.programs.waiting = Some(ProgramWait::Goto),
+            CalcButtons::Test => self.programs.waiting = Some(ProgramWait::Test),
+            CalcButtons::SingleStep | CalcButtons::Execute => {
+                info!("{:?}: not a program step", button);
+            },
+            _ => self.programs.record(Step::Key(button)),
+        }
+    }
+
+    pub fn next_program_key(&mut self) -> Option<CalcButtons> { // The next key the running program presses, if any
+        if self.error.is_some() {
+            if self.programs.run.take().is_some() {
+                info!("Program {} stopped on an error", self.programs.selected);
+            }
+            return None;
+        }
+        match self.programs.next_key(self.showing(), self.sum.precision) {
+            Ok(button) => button,
+            Err(error) => {
+                self.error = Some(error);
+                None
+            },
+        }
+    }
+
+    pub fn wants_register(&self) -> bool { // The letter keys pick a register, for STO and RCL or their program steps
+        self.registers.browse.is_some() || (self.programs.recording && matches!(
+            self.programs.slots[self.programs.selected].last(),
+            Some(Step::Key(CalcButtons::Store | CalcButtons::Recall))
+        ))
+    }
+
+    fn pick_constant(&mut self, button: CalcButtons) { // The keypad steps through the CONST list while it is open
+        match button {
+            CalcButtons::Add | CalcButtons::StatReviewPrev => self.constants.prev(),
+            CalcButtons::Subtract | CalcButtons::StatReviewNext => self.constants.next(),
+            CalcButtons::Sum | CalcButtons::Constant => {
+                self.constants.close();
+                if let Some(constant) = self.constants.current().cloned() {
+                    self.start_recall();
+                    match self.mode {
+                        CalcMode::Programmer => self.load_entry(Number::Real(constant.value)),
+                        _ => self.var.load_held(Number::Real(constant.value)), // Every digit, past what can be typed
+                    }
+                    self.notice = Some(constant.name);
+                }
+            },
+            CalcButtons::Clear | CalcButtons::ClearEntry => self.constants.close(),
+            _ => match button.digit() {
+                Some(0) => self.constants.jump(9), // 1 to 9 and 0 reach the first ten
+                Some(digit) if digit < 10 => self.constants.jump(digit as usize - 1),
+                _ => info!("{:?}: the CONST list is open, = picks and AC closes", button),
+            },
+        }
+    }
+
+    fn tape_op(&mut self, op: PendingOp) {
+        match self.tape.last() {
+            Some(Token::Op(_)) => {
+                self.tape.pop(); // A second operator in a row replaces the first
+            },
+            None => self.tape.push(Token::Number(self.sum.sum)), // Continuing on from the last result
+            _ => {},
+        }
+        self.tape.push(Token::Op(op));
+    }
+
+    fn record(&mut self) { // A finished calculation goes into the history
+        let tokens: Vec<Token<Number>> = std::mem::take(&mut self.tape);
+        if self.error.is_some() || !tokens.iter().any(|token| matches!(token, Token::Op(_))) {
+            return; // A lone number is not a calculation
+        }
+        let mut tape: String = String::new();
+        for token in &tokens {
+            let text: String = match token {
+                Token::Number(value) => self.format(*value),
+                Token::Op(op) => op.symbol().to_string(),
+                Token::LParen => "(".to_string(),
+                Token::RParen => ")".to_string(),
+            };
+            if !tape.is_empty() && !tape.ends_with('(') && *token != Token::RParen {
+                tape.push(' ');
+            }
+            tape += &text;
+        }
+        tape += &format!(" = {}", self.format(self.sum.sum));
+        self.history.record(Calculation::new(tokens, self.sum.sum, tape));
+    }
+
+    fn memory_update(&mut self, op: PendingOp) {
+        // M+ and M- finish the calculation first, then fold its result into memory
+        match self.entry_mode {
+            EntryMode::Rpn => self.rpn_fold(), // X goes to memory, the stack is left alone
+            _ => sum_calc_operations(self, CalcOperations::Sum),
+        }
+        if self.error.is_some() {
+            return;
+        }
+        match self.apply_op(op, self.memory, self.sum.sum) {
+            Ok(memory) => self.memory = memory,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn operator(&mut self, next: PendingOp) {
+        match self.entry_mode {
+            EntryMode::Chain => self.chain_op(next),
+            EntryMode::Algebraic => self.algebraic_op(next),
+            EntryMode::Rpn => self.rpn_op(next),
+        }
+    }
+
+    fn rpn_fold(&mut self) { // A typed number becomes X
+        if self.has_entry() {
+            self.sum.sum = self.entry();
+        }
+        self.var.clear();
+    }
+
+    fn lift_stack(&mut self) { // T falls off the top
+        self.stack = [self.sum.sum, self.stack[0], self.stack[1]];
+    }
+
+    fn rpn_enter(&mut self) {
+        self.rpn_fold();
+        self.lift_stack();
+        self.stack_lift = false; // X stays as a copy for the next number to overwrite
+    }
+
+    fn rpn_op(&mut self, op: PendingOp) {
+        // Y op X, then the stack drops with T copied down
+        self.rpn_fold();
+        let x: Number = self.sum.sum;
+        match self.apply_op(op, self.stack[0], x) {
+            Ok(value) => {
+                self.tape = vec![Token::Number(self.stack[0]), Token::Op(op), Token::Number(x)];
+                self.last_x = x;
+                self.sum.sum = value;
+                self.stack = [self.stack[1], self.stack[2], self.stack[2]];
+                self.record();
+            },
+            Err(error) => self.error = Some(error),
+        }
+        self.stack_lift = true;
+    }
+
+    fn chain_op(&mut self, next: PendingOp) {
+        // Only fold the input into the sum when it was typed since the last operation
+        if self.has_entry() {
+            self.tape.push(Token::Number(self.entry()));
+            self.fold_entry();
+        }
+        self.tape_op(next);
+        self.last_op = Some(next);
+        self.var.clear();
+    }
+
+    fn algebraic_op(&mut self, next: PendingOp) {
+        if self.has_entry() {
+            self.expression.push(Token::Number(self.entry()));
+        } else if let Some(Token::Op(_)) = self.expression.last() {
+            self.expression.pop(); // A second operator in a row replaces the first
+        } else if self.expression.is_empty() {
+            self.expression.push(Token::Number(self.sum.sum)); // Continue on from the last result
+        }
+        // Show what the complete part of the expression comes to so far
+        match self.evaluate() {
+            Ok(value) => self.sum.sum = value,
+            Err(CalcError::InvalidInput) => {}, // Still inside an open group
+            Err(error) => self.error = Some(error),
+        }
+        self.expression.push(Token::Op(next));
+        self.last_op = Some(next);
+        self.var.clear();
+    }
+
+    fn unary(&mut self, op: UnaryOp) {
+        let complex: bool = self.mode == CalcMode::Complex;
+        self.apply_function(CalcOperations::Unary(op), |engine, value| {
+            let input: Number = match value.to_complex(engine.sum.precision) {
+                Ok(value) if complex => Number::Complex(value), // So a negative real can still take a square root
+                _ => value,
+            };
+            Number::unary(op, input, engine.sum.precision, engine.angle_mode)
+        });
+    }
+
+    fn convert(&mut self) {
+        self.apply_function(CalcOperations::Convert, |engine, value| {
+            let value: Decimal = value.to_decimal(engine.sum.precision)?;
+            let converted: Decimal = match engine.mode {
+                CalcMode::Currency => engine.currency.convert(value, engine.sum.precision)?,
+                _ => engine.converter.convert(value, engine.sum.precision)?,
+            };
+            Ok(Number::Real(converted))
+        });
+    }
+
+    fn percent(&mut self) {
+        // b % becomes the operand the pending operator wants, then finishes as = would:
+        // a + b % adds b percent of a, a × b % is b percent of a, a ÷ b % is a as a percent of b
+        let hundred: Number = Number::Real(Decimal::from(100));
+        if self.entry_mode == EntryMode::Rpn { // X becomes X percent of Y, and Y stays for the + or - that follows
+            self.rpn_fold();
+            let x: Number = self.sum.sum;
+            match self.apply_op(PendingOp::Multiply, self.stack[0], x).and_then(|value| self.apply_op(PendingOp::Divide, value, hundred)) {
+                Ok(value) => {
+                    self.last_x = x;
+                    self.sum.sum = value;
+                },
+                Err(error) => self.error = Some(error),
+            }
+            self.stack_lift = true;
+            self.index = CalcOperations::Sum;
+            return;
+        }
+        let pending: Option<PendingOp> = match self.entry_mode {
+            EntryMode::Algebraic => match self.expression.last() {
+                Some(Token::Op(op)) => Some(*op),
+                _ => None,
+            },
+            _ => self.last_op,
+        };
+        let Some(op) = pending else {
+            self.apply_function(CalcOperations::Percent, |engine, value| engine.apply_op(PendingOp::Divide, value, hundred));
+            return; // Standalone, b % is b / 100
+        };
+        let percent: Number = self.showing();
+        let operand: Result<Number, CalcError> = match op {
+            PendingOp::Add | PendingOp::Subtract => { // The sum so far is the a the percentage is of
+                self.apply_op(PendingOp::Multiply, self.sum.sum, percent).and_then(|value| self.apply_op(PendingOp::Divide, value, hundred))
+            },
+            PendingOp::Markup | PendingOp::PercentChange => Ok(percent), // Already take b as a percentage
+            _ => self.apply_op(PendingOp::Divide, percent, hundred),
+        };
+        match operand {
+            Ok(operand) => {
+                self.load_entry(self.wrap(operand));
+                self.index = CalcOperations::Init;
+                sum_calc_operations(self, CalcOperations::Sum);
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn apply_function<F>(&mut self, index: CalcOperations, function: F)
+    where
+        F: Fn(&Self, Number) -> Result<Number, CalcError>,
+    {
+        if self.entry_mode == EntryMode::Rpn {
+            self.rpn_fold();
+            let x: Number = self.sum.sum;
+            match function(self, x).map(|value| self.wrap(value)) {
+                Ok(value) => {
+                    self.last_x = x;
+                    self.sum.sum = value;
+                },
+                Err(error) => self.error = Some(error),
+            }
+            self.stack_lift = true;
+            self.index = CalcOperations::Sum;
+            return;
+        }
+        if self.entry_mode == EntryMode::Algebraic && self.index == CalcOperations::CloseParen {
+            info!("{:?}: a closed group cannot take a function in algebraic entry", index);
+            return;
+        }
+        // Functions act on what is showing: the entry, or the sum when nothing was typed
+        let to_entry: bool = self.has_entry() || self.operand_pending();
+        let input: Number = if self.has_entry() { self.entry() } else { self.sum.sum };
+        match function(self, input).map(|value| self.wrap(value)) {
+            Ok(value) if to_entry => {
+                self.load_entry(value);
+                self.index = index;
+            },
+            Ok(value) => {
+                self.sum.sum = value;
+                self.index = CalcOperations::Sum;
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn showing(&self) -> Number { // The entry, or the sum when nothing was typed
+        if self.has_entry() { self.entry() } else { self.sum.sum }
+    }
+
+    fn show_result(&mut self, value: Number) { // Shown as the result of a finished calculation
+        if self.entry_mode == EntryMode::Rpn {
+            if self.has_entry() {
+                self.rpn_fold();
+                self.stack_lift = true;
+            }
+            if self.stack_lift {
+                self.lift_stack();
+            }
+            self.stack_lift = true;
+        }
+        self.var.clear();
+        self.last_op = None;
+        self.repeat = None;
+        self.expression.clear();
+        self.paren_stack.clear();
+        self.tape.clear();
+        self.sum.sum = value;
+    }
+
+    fn chain_sum(&mut self) {
+        if !self.paren_stack.is_empty() {
+            while !self.paren_stack.is_empty() { // Sum closes any open groups first
+                self.close_paren();
+            }
+            self.record();
+            return;
+        }
+        if self.index == CalcOperations::Sum {
+            if let Some((op, operand)) = self.repeat { // = = = keeps reapplying the last step
+                self.tape = vec![Token::Number(self.sum.sum), Token::Op(op), Token::Number(operand)];
+                self.apply_step(op, self.sum.sum, operand);
+            }
+        } else if self.has_entry() {
+            let entry: Number = self.entry();
+            match (self.last_op, self.repeat) {
+                (Some(op), _) => {
+                    self.tape.push(Token::Number(entry));
+                    self.apply_step(op, self.sum.sum, entry);
+                },
+                (None, Some((op, operand))) => { // Constant: new number, same step
+                    self.tape = vec![Token::Number(entry), Token::Op(op), Token::Number(operand)];
+                    self.apply_step(op, entry, operand);
+                },
+                (None, None) => self.sum.sum = entry,
+            }
+        } else if let Some(op) = self.last_op {
+            self.tape.push(Token::Number(self.sum.sum));
+            self.apply_step(op, self.sum.sum, self.sum.sum); // "2 × =" uses the sum as its own operand
+        }
+        self.last_op = None;
+        self.record();
+    }
+
+    fn apply_step(&mut self, op: PendingOp, lhs: Number, operand: Number) {
+        match self.apply_op(op, lhs, operand) {
+            Ok(value) => {
+                self.sum.sum = value;
+                self.repeat = Some((op, operand));
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn algebraic_sum(&mut self) {
+        if self.has_entry() {
+            self.expression.push(Token::Number(self.entry()));
+        }
+        if self.expression.is_empty() {
+            if let (CalcOperations::Sum, Some((op, operand))) = (self.index, self.repeat) {
+                self.tape = vec![Token::Number(self.sum.sum), Token::Op(op), Token::Number(operand)];
+                self.apply_step(op, self.sum.sum, operand);
+                self.record();
+            }
+            return;
+        }
+        if let ([Token::Number(value)], Some((op, operand))) = (&self.expression[..], self.repeat) {
+            let value: Number = *value;
+            self.expression.clear();
+            self.tape = vec![Token::Number(value), Token::Op(op), Token::Number(operand)];
+            self.apply_step(op, value, operand);
+            self.record();
+            return;
+        }
+        if let Some(Token::Op(_)) = self.expression.last() {
+            // "2 × =" reuses the last operand entered
+            let last_operand = self.expression.iter().rev().find_map(|token| match token {
+                Token::Number(value) => Some(*value),
+                _ => None,
+            });
+            if let Some(value) = last_operand {
+                self.expression.push(Token::Number(value));
+            }
+        }
+        while let Some(Token::Op(_)) | Some(Token::LParen) = self.expression.last() {
+            self.expression.pop(); // Drop whatever was left dangling
+        }
+        if self.expression.is_empty() {
+            return;
+        }
+        if let [.., Token::Op(op), Token::Number(operand)] = self.expression[..] {
+            self.repeat = Some((op, operand));
+        }
+        for _ in 0..self.depth() {
+            self.expression.push(Token::RParen); // Auto-close open groups
+        }
+        match self.evaluate() {
+            Ok(value) => self.sum.sum = value,
+            Err(err) => self.error = Some(err),
+        }
+        self.tape = std::mem::take(&mut self.expression);
+        self.record();
+    }
+
+    fn open_paren(&mut self) {
+        match self.entry_mode {
+            EntryMode::Rpn => return, // The stack holds the operands instead
+            EntryMode::Chain => {
+                // The group becomes the operand of whichever operator is waiting, "2(" reads as 2×(
+                let pending = match self.index {
+                    CalcOperations::Add
+                    | CalcOperations::Subtract
+                    | CalcOperations::Multiply
+                    | CalcOperations::Divide
+                    | CalcOperations::Power
+                    | CalcOperations::Modulo
+                    | CalcOperations::And
+                    | CalcOperations::Or
+                    | CalcOperations::Xor
+                    | CalcOperations::ShiftLeft
+                    | CalcOperations::ShiftRight => self.last_op,
+                    CalcOperations::Init if self.has_entry() => {
+                        self.chain_op(PendingOp::Multiply);
+                        self.last_op
+                    },
+                    CalcOperations::Init => self.last_op, // Entry was cleared with CE, its operator still waits
+                    _ => None,
+                };
+                let outer: Number = if pending.is_some() { self.sum.sum } else { Number::ZERO };
+                if pending.is_none() {
+                    self.tape.clear(); // The group starts a new calculation
+                }
+                self.tape.push(Token::LParen);
+                self.paren_stack.push((outer, pending));
+                self.sum.zero();
+                self.last_op = None;
+            },
+            EntryMode::Algebraic => {
+                if self.has_entry() {
+                    self.algebraic_op(PendingOp::Multiply);
+                }
+                self.expression.push(Token::LParen);
+            },
+        }
+        self.var.clear();
+    }
+
+    fn close_paren(&mut self) {
+        if self.depth() == 0 {
+            self.error = Some(CalcError::InvalidInput);
+            return;
+        }
+        match self.entry_mode {
+            EntryMode::Rpn => {}, // Never open, so caught above
+            EntryMode::Chain => {
+                if self.has_entry() {
+                    self.tape.push(Token::Number(self.entry()));
+                    self.fold_entry();
+                }
+                if let Some(Token::Op(_)) = self.tape.last() {
+                    self.tape.pop();
+                }
+                self.tape.push(Token::RParen);
+                let inner: Number = self.sum.sum;
+                if let Some((outer, pending)) = self.paren_stack.pop() {
+                    let group = match pending {
+                        Some(op) => self.apply_op(op, outer, inner),
+                        None => Ok(inner),
+                    };
+                    match group {
+                        Ok(value) => self.sum.sum = value,
+                        Err(error) => self.error = Some(error),
+                    }
+                }
+                self.last_op = None;
+            },
+            EntryMode::Algebraic => {
+                if self.has_entry() {
+                    self.expression.push(Token::Number(self.entry()));
+                }
+                if let Some(Token::Op(_)) = self.expression.last() {
+                    self.expression.pop();
+                }
+                self.expression.push(Token::RParen);
+                if let Ok(value) = self.evaluate() {
+                    self.sum.sum = value;
+                }
+            },
+        }
+        self.var.clear();
+    }
+}
+
+impl Default for CalcEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayState {
+    pub input: String,
+    pub sum: String,
+    pub depth: usize,
+    pub memory: bool,
+    pub entry_mode: EntryMode,
+    pub error: Option<CalcError>,
+    pub angle_mode: AngleMode,
+    pub shift: bool,
+    pub mode: CalcMode,
+    pub int_format: IntFormat,
+    pub show_fraction: bool,
+    pub polar: bool,
+    pub sum_parts: Option<(String, String)>, // Real and imaginary, or magnitude and angle, in complex mode
+    pub stack: Option<[String; 4]>, // X, Y, Z and T in RPN
+    pub units: String, // Conversion or currency mode's from and to, such as "m>ft" or "USD>EUR"
+    pub stats: String, // Statistics mode's sample count, or the sample under review
+    pub review: Option<String>, // The sample under review, such as "#2 3,4.5", until a replacement is typed
+    pub constant: Option<String>, // Name of the constant the CONST list is on, or was just inserted
+    pub picker: Option<String>, // The CONST list's place and value, such as "3/11 φ 1.618", while it is open
+    pub browse: Option<RegisterAction>, // STO or RCL, waiting on a register
+    pub register: Option<String>, // The register the browser is on, such as "B: 42"
+    pub program: Option<String>, // The step just recorded, such as "1-03 Add", or the digit a programming key waits on
+    pub program_status: Option<String>, // "PRGM 1" recording, "RUN 1" running, or "1-04" paused before step 4
+    pub tvm: String, // Finance mode's last variable, payment timing and P/Y, such as "PMT END 12"
+    pub amort: Option<String>, // The amortization schedule's period and figure, such as "P3 INT -41.67", while it is open
+    pub business: String, // Business mode's last figure set and tax rate, such as "MAR TAX 8%"
+    pub grand_total: bool, // GT holds something
+}
+
+impl DisplayState {
+    pub fn status_line(&self) -> String { // Indicators shown above the input on the device screen
+        let mut status: Vec<String> = Vec::new();
+        if self.error.is_some() {
+            status.push("E".to_string());
+        }
+        if self.shift {
+            status.push("2ND".to_string());
+        }
+        if self.memory {
+            status.push("M".to_string());
+        }
+        if self.grand_total {
+            status.push("GT".to_string());
+        }
+        match self.entry_mode {
+            EntryMode::Chain => {},
+            EntryMode::Algebraic => status.push("ALG".to_string()),
+            EntryMode::Rpn => status.push("RPN".to_string()),
+        }
+        if self.depth > 0 {
+            status.push(format!("({}", self.depth));
+        }
+        if let Some(action) = self.browse {
+            status.push(action.label().to_string());
+        }
+        if let Some(program) = &self.program_status {
+            status.push(program.clone());
+        }
+        if let Some(constant) = &self.constant {
+            status.push(format!("CONST {}", constant));
+            return status.join(" "); // The name can be long, the mode can wait
+        }
+        match self.mode {
+            CalcMode::Standard => status.push(self.angle_mode.label().to_string()),
+            CalcMode::Programmer => status.push(self.int_format.label()),
+            CalcMode::Fraction if self.show_fraction => status.push("FRAC".to_string()),
+            CalcMode::Fraction => status.push("FRAC D".to_string()),
+            CalcMode::Complex if self.polar => status.push(format!("POLAR {}", self.angle_mode.label())),
+            CalcMode::Complex => status.push(format!("CPLX {}", self.angle_mode.label())),
+            CalcMode::Convert | CalcMode::Currency => status.push(self.units.clone()),
+            CalcMode::Stats => status.push(format!("STAT {}", self.stats)),
+            CalcMode::Finance => status.push(format!("FIN {}", self.tvm)),
+            CalcMode::Business => status.push(format!("BIZ {}", self.business)),
+        }
+        status.join(" ")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalcError {
+    DivideByZero,
+    Overflow,
+    Domain,       // Outside what the function is defined for
+    InvalidInput, // Malformed input, such as an unmatched parenthesis
+    NoRate,       // A currency missing from the rate table
+    NoSolution,   // Time value of money figures no value can satisfy
+}
+
+impl CalcError {
+    pub fn label(&self) -> &'static str { // Short enough for the device screen
+        match self {
+            CalcError::DivideByZero => "Div 0",
+            CalcError::Overflow => "Overload",
+            CalcError::Domain => "Domain",
+            CalcError::InvalidInput => "Syntax",
+            CalcError::NoRate => "No Rate",
+            CalcError::NoSolution => "No Soln",
         }
     }
 }
 
 pub trait FlexInput {
-    fn to_f64(self) -> f64;
+    fn to_decimal(self) -> Result<Decimal, CalcError>;
+}
+
+impl FlexInput for Decimal {
+    fn to_decimal(self) -> Result<Decimal, CalcError> {
+        Ok(self)
+    }
 }
 
 impl FlexInput for f64 {
-    fn to_f64(self) -> f64 {
-        self
+    fn to_decimal(self) -> Result<Decimal, CalcError> {
+        Decimal::from_f64(self)
     }
 }
 
 impl FlexInput for usize {
-    fn to_f64(self) -> f64 {
-        self as f64
+    fn to_decimal(self) -> Result<Decimal, CalcError> {
+        Ok(Decimal::from(self))
     }
 }
 
 impl FlexInput for u32 {
-    fn to_f64(self) -> f64 {
-        self as f64
+    fn to_decimal(self) -> Result<Decimal, CalcError> {
+        Ok(Decimal::from(self))
     }
 }
 
 impl FlexInput for i32 {
-    fn to_f64(self) -> f64 {
-        self as f64
+    fn to_decimal(self) -> Result<Decimal, CalcError> {
+        Ok(Decimal::from(self))
     }
 }
 
 #[derive(Clone, Resource)]
 pub struct OpIndex {
-    pub index: u32,
-    pub last_op: u32,
     pub screen_color: u32,
     pub entities: u32,
 }
@@ -86,10 +1650,8 @@ pub struct OpIndex {
 impl OpIndex {
     pub fn new(    
     ) -> Self {
-        let (index, last_op, screen_color, entities): (u32, u32, u32, u32) = (0, 0, 0, 9); // Startup Entities cannot be captured so they're hardcoded.
+        let (screen_color, entities): (u32, u32) = (0, 9); // Startup Entities cannot be captured so they're hardcoded.
         OpIndex {
-            index,
-            last_op,
             screen_color,
             entities,
         }
@@ -101,36 +1663,211 @@ impl OpIndex {
     }
 }
 
-#[derive(Debug)]
+impl Default for OpIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CalcOperations {
-    Init,
+    Init, // Input is being entered
     Clear,
     Add,
     Subtract,
     Multiply,
     Divide,
     Sum,
+    ClearEntry,
+    OpenParen,
+    CloseParen,
+    MemoryClear,
+    MemoryRecall,
+    MemoryAdd,
+    MemorySubtract,
+    Power,
+    Modulo,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+    Unary(UnaryOp),
+    Swap,     // RPN x<>y
+    RollDown, // RPN R↓
+    LastX,
+    Convert, // Conversion mode, between the selected units
+    StatAdd,    // Σ+
+    StatRemove, // Σ-
+    Statistic(Statistic),
+    Tvm(TvmVar), // Stores the value typed, or solves for the variable
+    Markup,        // Selling price for a margin on the cost: cost MU margin% =
+    PercentChange, // Δ%, from one value to the next: old Δ% new =
+    TaxAdd,
+    TaxRemove,
+    Margin(MarginVar), // Stores the value typed, or solves from the other two
+    GrandTotal,        // Recalls GT as the input
+    Percent,           // Takes its meaning from the pending operator
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingOp { // The operator waiting on its right-hand operand
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Modulo,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+    Markup,
+    PercentChange,
 }
 
-impl CalcOperations {
-    pub fn from_index(index: u32) -> Option<CalcOperations> {
-        match index {
-            0 => Some(CalcOperations::Init),
-            1 => Some(CalcOperations::Clear),
-            2 => Some(CalcOperations::Add),
-            3 => Some(CalcOperations::Subtract),
-            4 => Some(CalcOperations::Multiply),
-            5 => Some(CalcOperations::Divide),
-            6 => Some(CalcOperations::Sum),
-            _ => None, // Handle invalid index
+impl PendingOp {
+    pub fn precedence(&self) -> u32 { // Bitwise operators bind as they do in C
+        match self {
+            PendingOp::Or => 1,
+            PendingOp::Xor => 2,
+            PendingOp::And => 3,
+            PendingOp::ShiftLeft | PendingOp::ShiftRight => 4,
+            PendingOp::Add | PendingOp::Subtract => 5,
+            PendingOp::Multiply | PendingOp::Divide | PendingOp::Modulo | PendingOp::Markup | PendingOp::PercentChange => 6,
+            PendingOp::Power => 7,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str { // As written on the history tape
+        match self {
+            PendingOp::Add => "+",
+            PendingOp::Subtract => "-",
+            PendingOp::Multiply => "×",
+            PendingOp::Divide => "÷",
+            PendingOp::Power => "^",
+            PendingOp::Modulo => "mod",
+            PendingOp::And => "AND",
+            PendingOp::Or => "OR",
+            PendingOp::Xor => "XOR",
+            PendingOp::ShiftLeft => "<<",
+            PendingOp::ShiftRight => ">>",
+            PendingOp::Markup => "MU",
+            PendingOp::PercentChange => "Δ%",
+        }
+    }
+
+    pub fn right_associative(&self) -> bool {
+        matches!(self, PendingOp::Power) // 2^3^2 = 2^9
+    }
+
+    pub fn apply(&self, lhs: Decimal, rhs: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+        match self {
+            PendingOp::Add => lhs.checked_add(rhs),
+            PendingOp::Subtract => lhs.checked_sub(rhs),
+            PendingOp::Multiply => lhs.checked_mul(rhs, precision),
+            PendingOp::Divide => lhs.checked_div(rhs, precision),
+            PendingOp::Power => lhs.checked_pow(rhs, precision),
+            PendingOp::Modulo => lhs.checked_rem(rhs),
+            PendingOp::And => Ok(Decimal::new(lhs.to_integer()? & rhs.to_integer()?, 0)),
+            PendingOp::Or => Ok(Decimal::new(lhs.to_integer()? | rhs.to_integer()?, 0)),
+            PendingOp::Xor => Ok(Decimal::new(lhs.to_integer()? ^ rhs.to_integer()?, 0)),
+            PendingOp::ShiftLeft => lhs.checked_mul(Decimal::from(2).checked_pow(rhs, 0)?, 0),
+            PendingOp::ShiftRight => {
+                let shift: i128 = rhs.to_integer()?;
+                if shift < 0 {
+                    return Err(CalcError::Domain);
+                }
+                Ok(Decimal::new(lhs.to_integer()? >> shift.min(127), 0))
+            },
+            PendingOp::Markup => { // The price that leaves rhs percent of it as margin over the cost
+                let hundred: Decimal = Decimal::from(100);
+                lhs.checked_div(hundred.checked_sub(rhs)?.checked_div(hundred, precision)?, precision)
+            },
+            PendingOp::PercentChange => rhs.checked_sub(lhs)?.checked_div(lhs, precision)?.checked_mul(Decimal::from(100), precision),
         }
     }
 }
 
-#[derive(Clone, Resource)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp { // Functions applied straight away to the value showing
+    SquareRoot,
+    Square,
+    Reciprocal,
+    Sin,
+    Cos,
+    Tan,
+    ArcSin,
+    ArcCos,
+    ArcTan,
+    Log10,
+    Ln,
+    Exp,
+    Factorial,
+    Not,
+    Magnitude,
+    Argument,  // Angle from the positive real axis
+    Conjugate,
+}
+
+impl UnaryOp {
+    pub fn apply(&self, value: Decimal, precision: u32, angle_mode: AngleMode) -> Result<Decimal, CalcError> {
+        let x: f64 = value.to_f64();
+        let res: f64 = match self {
+            UnaryOp::Square => return value.checked_mul(value, precision),
+            UnaryOp::Reciprocal => return Decimal::ONE.checked_div(value, precision),
+            UnaryOp::Factorial => return factorial(value),
+            UnaryOp::Not => return Ok(Decimal::new(!value.to_integer()?, 0)), // Wrapped to the word by the engine
+            UnaryOp::Magnitude => return value.checked_abs(),
+            UnaryOp::Conjugate => return Ok(value),
+            UnaryOp::Argument if !value.is_negative() => return Ok(Decimal::ZERO),
+            UnaryOp::Argument => angle_mode.angle(std::f64::consts::PI),
+            UnaryOp::SquareRoot if value.is_negative() => return Err(CalcError::Domain),
+            UnaryOp::SquareRoot => x.sqrt(),
+            UnaryOp::Sin => angle_mode.radians(x).sin(),
+            UnaryOp::Cos => angle_mode.radians(x).cos(),
+            UnaryOp::Tan => {
+                let radians: f64 = angle_mode.radians(x);
+                if radians.cos().abs() < 1e-12 {
+                    return Err(CalcError::Domain); // Odd multiples of 90 degrees
+                }
+                radians.tan()
+            },
+            UnaryOp::ArcSin | UnaryOp::ArcCos if x.abs() > 1.0 => return Err(CalcError::Domain),
+            UnaryOp::ArcSin => angle_mode.angle(x.asin()),
+            UnaryOp::ArcCos => angle_mode.angle(x.acos()),
+            UnaryOp::ArcTan => angle_mode.angle(x.atan()),
+            UnaryOp::Log10 | UnaryOp::Ln if x <= 0.0 => return Err(CalcError::Domain),
+            UnaryOp::Log10 => x.log10(),
+            UnaryOp::Ln => x.ln(),
+            UnaryOp::Exp => x.exp(),
+        };
+        // f64 noise such as sin(180) = 1.2e-16 falls away in the rounding
+        Decimal::from_f64(res)?.round(precision)
+    }
+}
+
+fn factorial(value: Decimal) -> Result<Decimal, CalcError> {
+    if value.is_negative() || value.scale() > 0 {
+        return Err(CalcError::Domain); // Whole numbers only
+    }
+    let mut res: Decimal = Decimal::ONE;
+    let mut i: i128 = 2;
+    while i <= value.mantissa() {
+        res = res.checked_mul(Decimal::new(i, 0), 0)?; // Overflows past 33!
+        i += 1;
+    }
+    Ok(res)
+}
+
+#[derive(Clone, PartialEq, Resource)]
 pub struct SumVariable {
     pub var: Vec<i32>,
     pub decimal_index: i32,
+    pub negative: bool,
+    pub held: Option<Number>, // A fraction or complex result standing in for typed digits
+    pub imaginary: bool, // The digits are the coefficient of i
 }
 
 impl SumVariable {
@@ -140,19 +1877,92 @@ impl SumVariable {
         SumVariable {
             var,
             decimal_index,
+            negative: false,
+            held: None,
+            imaginary: false,
         }
     }
 
+    pub fn load_held(&mut self, value: Number) { // Fractions and complex numbers cannot be typed, so they are held whole
+        self.clear();
+        self.held = Some(value);
+    }
+
+    pub fn load(&mut self, value: Decimal) -> Result<(), CalcError> { // Replace the input with a stored value, as if it were typed
+        self.load_text(&value.to_string(), MAX_PRECISION as usize)
+    }
+
+    pub fn load_text(&mut self, text: &str, limit: usize) -> Result<(), CalcError> { // Digits up to base 16, '.' and '-'
+        if text.chars().filter(|c| c.is_ascii_hexdigit()).count() > limit {
+            return Err(CalcError::Overflow); // More digits than the input can hold
+        }
+        self.clear();
+        for c in text.chars() {
+            match c {
+                '-' => self.negative = true,
+                '.' => self.decimal(),
+                _ => match c.to_digit(16) {
+                    Some(digit) => self.push_within(digit as i32, limit),
+                    None => return Err(CalcError::InvalidInput),
+                },
+            }
+        }
+        Ok(())
+    }
+
     pub fn review(&self) {
         info!("Review: var.vec {:?}", self.var);
         info!("Review: var.index {:?}", self.decimal_index);
     }
 
     pub fn push(&mut self, input: i32) {
-        self.var.push(input);
+        self.push_within(input, MAX_PRECISION as usize); // Keeps the rebuilt mantissa inside i128
+    }
+
+    pub fn push_within(&mut self, input: i32, limit: usize) {
+        if self.held.is_some() {
+            self.clear(); // Typing replaces a held value
+        }
+        if self.var.len() < limit {
+            self.var.push(input);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.held.is_some() {
+            self.clear();
+            return;
+        }
+        if self.decimal_index > 0 && self.decimal_index as usize == self.var.len() {
+            self.decimal_index = 0; // The decimal point was the last thing entered
+        } else {
+            self.var.pop();
+        }
+        if self.var.is_empty() && !self.imaginary {
+            self.clear();
+        }
+    }
+
+    pub fn toggle_sign(&mut self) -> Result<(), CalcError> {
+        match self.held {
+            Some(value) => self.held = Some(value.checked_neg()?),
+            None => self.negative = !self.negative,
+        }
+        Ok(())
+    }
+
+    pub fn toggle_imaginary(&mut self) {
+        if self.held.is_some() {
+            self.clear();
+        }
+        self.imaginary = !self.imaginary;
     }
 
     pub fn decimal(&mut self) {
+        if self.held.is_some() {
+            self.clear();
+            self.push(0);
+        }
         if self.decimal_index == 0 {
             let len: i32 = self.var.len() as i32;
             self.decimal_index = len;
@@ -163,204 +1973,222 @@ impl SumVariable {
 
     pub fn clear(&mut self) {
         self.decimal_index = 0;
-        while self.var.len() > 0 {
+        self.negative = false;
+        self.held = None;
+        self.imaginary = false;
+        while !self.var.is_empty() {
             self.var.pop();
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.var.is_empty() && self.held.is_none() && !self.imaginary
+    }
+
+    pub fn to_display(&self) -> String { // Digits as typed, keeping a trailing decimal point
+        if let Some(value) = self.held {
+            return value.to_mixed();
+        }
+        let mut num: String = "".to_string();
+        for i in 0..self.var.len() {
+            if self.decimal_index > 0 && i == self.decimal_index as usize {
+                num += ".";
+            }
+            if let Some(c) = char::from_digit(self.var[i] as u32, 16) {
+                num.push(c.to_ascii_uppercase()); // Hex digits show as A-F
+            }
+        }
+        if self.decimal_index > 0 && self.decimal_index as usize == self.var.len() {
+            num += ".";
+        }
+        if self.imaginary {
+            num += "i"; // A bare i is one times i
+        } else if num.is_empty() {
+            num += "0";
+        }
+        if self.negative {
+            num.insert(0, '-');
+        }
+        num
+    }
+
+    pub fn to_int(&self, radix: u32) -> i128 { // Programmer entry, kept short enough by its word size to fit
+        let mut value: i128 = 0;
+        for digit in &self.var {
+            value = value.wrapping_mul(radix as i128).wrapping_add(*digit as i128);
+        }
+        if self.negative {
+            value.wrapping_neg()
+        } else {
+            value
+        }
+    }
+
+    pub fn to_decimal(&self) -> Decimal { // Digits become the mantissa, digits after the point the scale
+        if let Some(value) = self.held {
+            return value.to_decimal(DEFAULT_PRECISION).unwrap_or(Decimal::ZERO);
+        }
+        if self.var.is_empty() && self.imaginary {
+            return Decimal::new(if self.negative { -1 } else { 1 }, 0);
+        }
+        let mut mantissa: i128 = 0;
+        for digit in &self.var {
+            mantissa = mantissa * 10 + *digit as i128;
+        }
+        let scale: u32 = if self.decimal_index > 0 {
+            self.var.len() as u32 - self.decimal_index as u32
+        } else {
+            0
+        };
+        Decimal::new(if self.negative { -mantissa } else { mantissa }, scale) // Typed digits stay well inside i128
+    }
 }
 
-#[derive(Clone, Copy, Debug, Resource)]
+impl Default for SumVariable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Resource)]
 pub struct SumCurrent {
-    pub sum: f64,
+    pub sum: Number,
+    pub precision: u32, // Fractional digits kept after multiply and divide
 }
 
 impl SumCurrent {
     pub fn new() -> Self {
-        let sum: f64 = 0.0;
+        let sum: Number = Number::ZERO;
         SumCurrent{
             sum,
+            precision: DEFAULT_PRECISION,
         }
     }
 
-    pub fn var_to_sum_if_sum_zero(
-        var: &mut ResMut<SumVariable>,
-        sum: &mut ResMut<SumCurrent>,
-    ) {
-        if var.decimal_index > 0 && sum.sum == 0.0 {
-            let mut num: String = "".to_string();
-            let mut multiplier: String = ".".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            for _ in 0..var.var.len() - var.decimal_index as usize - 1 {
-                multiplier += "0";
-            }
-            multiplier += "1";
-
-            let res_num: f64 = num.to_string().parse::<f64>().unwrap();
-            let res_mul: f64 = multiplier.to_string().parse::<f64>().unwrap();
-            let res = res_num * res_mul;
-
-            sum.sum = res;
-
-        } else if var.decimal_index == 0 && sum.sum == 0.0 {
-            let mut num: String = "".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            let new_sum: f64 = if var.var.len() == 0 {
-                0.0
-            } else {
-                num.to_string().parse::<f64>().unwrap()
-            };
-
-            sum.sum = new_sum;
+    pub fn with_precision(precision: u32) -> Self {
+        SumCurrent{
+            sum: Number::ZERO,
+            precision: precision.min(MAX_PRECISION),
         }
     }
 
     pub fn update_sum(
-        var: &mut ResMut<SumVariable>,
-        sum: &mut ResMut<SumCurrent>,
-        op: &mut ResMut<OpIndex>,
-    ) { // rebuild vec from SumVariable into f64 and pass it into the sum with maths if applicable
-            if var.decimal_index > 0 && sum.sum == 0.0 {
-            let mut num: String = "".to_string();
-            let mut multiplier: String = ".".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            for _ in 0..var.var.len() - var.decimal_index as usize - 1 {
-                multiplier += "0";
-            }
-            multiplier += "1";
-
-            let res_num: f64 = num.to_string().parse::<f64>().unwrap();
-            let res_mul: f64 = multiplier.to_string().parse::<f64>().unwrap();
-            let res = res_num * res_mul;
-
-            sum.sum = res;
-
-        } else if var.decimal_index == 0 && sum.sum == 0.0 {
-            let mut num: String = "".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            let new_sum: f64 = if var.var.len() == 0 {
-                0.0
-            } else {
-                num.to_string().parse::<f64>().unwrap()
-            };
-
-            sum.sum = new_sum;
-
-        } else if var.decimal_index > 0 && sum.sum != 0.0 {
-            let mut num: String = "".to_string();
-            let mut multiplier: String = ".".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            for _ in 0..var.var.len() - var.decimal_index as usize - 1 {
-                multiplier += "0";
-            }
-            multiplier += "1";
-
-            let res_num: f64 = num.to_string().parse::<f64>().unwrap();
-            let res_mul: f64 = multiplier.to_string().parse::<f64>().unwrap();
-            let res = res_num * res_mul;
-
-            match op.last_op {
-                1 => {
-                    sum.sum += res;
-                },
-                2 => {
-                    sum.sum -= res;
-                },
-                3 => {
-                    sum.sum *= res;
-                },
-                4 => {
-                    sum.sum /= res;
-                },
-                _ => {}, // Handle invalid index
-            }
-            
-
-        } else if var.decimal_index == 0 && sum.sum != 0.0 {
-            let mut num: String = "".to_string();
-            for i in 0..var.var.len() {
-                num += &var.var[i].to_string();
-            }
-            let res: f64 = if var.var.len() == 0 {
-                0.0
-            } else {
-                num.to_string().parse::<f64>().unwrap()
-            };
-
-            match op.last_op {
-                1 => {
-                    sum.sum += res;
-                },
-                2 => {
-                    sum.sum -= res;
-                },
-                3 => {
-                    sum.sum *= res;
-                },
-                4 => {
-                    sum.sum /= res;
-                },
-                _ => {}, // Handle invalid index
-            }        
-        } else {
-            info!("update_sum: var.decimal_index: Failure, below 0 or invalid.")
+        &mut self,
+        var: &SumVariable,
+        last_op: Option<PendingOp>,
+    ) -> Result<(), CalcError> { // rebuild SumVariable into a Decimal and pass it into the sum with maths if applicable
+        if var.is_empty() {
+            return Ok(());
         }
+        let res: Number = Number::Real(var.to_decimal());
+        self.sum = match last_op {
+            Some(op) => Number::apply(op, self.sum, res, self.precision)?,
+            None => res,
+        };
+        Ok(())
     }
 
-    pub fn new_from<T: FlexInput>(input: T) -> Self {
-        let sum: f64 = input.to_f64();
-        SumCurrent{
+    pub fn new_from<T: FlexInput>(input: T) -> Result<Self, CalcError> {
+        let sum: Number = Number::Real(input.to_decimal()?);
+        Ok(SumCurrent{
             sum,
-        }
+            precision: DEFAULT_PRECISION,
+        })
     }
 
-    pub fn zero(        
-        sum: &mut ResMut<SumCurrent>,
-    ) {
-        let zero: f64 = 0.0;
-        sum.sum = zero;
+    pub fn zero(&mut self) {
+        let zero: Number = Number::ZERO;
+        self.sum = zero;
     }
 
-    pub fn add<T: FlexInput>(&mut self, input: T) -> Self {
-        let sum: f64 = self.sum + input.to_f64();
-        SumCurrent{
+    pub fn add<T: FlexInput>(&mut self, input: T) -> Result<Self, CalcError> {
+        let sum: Number = self.apply(PendingOp::Add, input)?;
+        Ok(SumCurrent{
             sum,
-        }
+            precision: self.precision,
+        })
     }
     
-    pub fn subtract<T: FlexInput>(&mut self, input: T) -> Self {
-        let sum: f64 = self.sum - input.to_f64();
-        SumCurrent{
+    pub fn subtract<T: FlexInput>(&mut self, input: T) -> Result<Self, CalcError> {
+        let sum: Number = self.apply(PendingOp::Subtract, input)?;
+        Ok(SumCurrent{
             sum,
-        }
+            precision: self.precision,
+        })
     }
     
-    pub fn multiply<T: FlexInput>(&mut self, input: T) -> Self {
-        let sum: f64 = self.sum * input.to_f64();
-        SumCurrent{
+    pub fn multiply<T: FlexInput>(&mut self, input: T) -> Result<Self, CalcError> {
+        let sum: Number = self.apply(PendingOp::Multiply, input)?;
+        Ok(SumCurrent{
             sum,
-        }
+            precision: self.precision,
+        })
     }
     
-    pub fn divide<T: FlexInput>(&mut self, input: T) -> Self {
-        let value = input.to_f64();
-        
-        if value != 0.0 {
-            let sum: f64 = self.sum / value;
-            SumCurrent{
-                sum,
-            }
-        } else {
-            panic!("Division by zero is not allowed");
+    pub fn divide<T: FlexInput>(&mut self, input: T) -> Result<Self, CalcError> {
+        let sum: Number = self.apply(PendingOp::Divide, input)?; // Zero is a DivideByZero error
+        Ok(SumCurrent{
+            sum,
+            precision: self.precision,
+        })
+    }
+
+    fn apply<T: FlexInput>(&self, op: PendingOp, input: T) -> Result<Number, CalcError> {
+        // A fraction sum stays exact against whole or decimal input
+        let rhs: Number = match self.sum {
+            Number::Real(_) | Number::Complex(_) => Number::Real(input.to_decimal()?),
+            Number::Ratio(_) => Number::exact(input.to_decimal()?),
+        };
+        Number::apply(op, self.sum, rhs, self.precision)
+    }
+}
+
+impl Default for SumCurrent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const REGISTER_COUNT: usize = 26;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterAction {
+    Store,
+    Recall,
+}
+
+impl RegisterAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RegisterAction::Store => "STO",
+            RegisterAction::Recall => "RCL",
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Resource)]
+pub struct Registers {
+    pub values: [Number; REGISTER_COUNT], // A to Z, kept through Clear and mode changes
+    pub selected: usize, // Where the browser is, and opens again
+    pub browse: Option<RegisterAction>, // STO or RCL is waiting on a register
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Registers {
+            values: [Number::ZERO; REGISTER_COUNT],
+            selected: 0,
+            browse: None,
+        }
+    }
+
+    pub fn name(register: usize) -> char { // 0 is A
+        (b'A' + register as u8) as char
+    }
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
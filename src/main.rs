@@ -7,21 +7,28 @@ use bevy::{prelude::*,
 
 use winit::window::Icon;
 
-use calc_sim::{OpIndex, SumCurrent, SumVariable};
+use calc_sim::{CalcEngine, OpIndex};
 
 use calc_sim::calculator::{cycle_screen_albedo, screen_albedo};
 use calc_sim::calculator::{CurrentMeshColor, ScreenAlbedoState};
 
-use calc_sim::cam_ui::setup_ui;
+use calc_sim::cam_ui::{recall_history, scroll_history, setup_ui, update_history_text, update_rates_text};
+use calc_sim::cam_ui::HistoryHovered;
 
 use calc_sim::cam_world::{draw_cursor, pan_orbit_camera, spawn_3d_camera};
 use calc_sim::cam_world::PanOrbitState;
 
-use calc_sim::cam_calc_screen::{setup_calc_interface_projection, update_sum_text, update_var_text};
+use calc_sim::cam_calc_screen::{expire_notice, setup_calc_interface_projection, update_status_text, update_sum_text, update_var_text};
 
-use calc_sim::game_env::{button_animation_system, dim_while_clicked, fire_ray, handle_asset_events, release_ray, body_animation_system, spawn_gltf};
+use calc_sim::game_env::{button_animation_system, dim_while_clicked, fire_ray, error_feedback, handle_asset_events, keyboard_input, release_ray, body_animation_system, run_program, spawn_gltf};
 use calc_sim::game_env::CountdownCycle;
 
+use calc_sim::constants::ConstantTable;
+use calc_sim::csv::load_table;
+use calc_sim::currency::RateTable;
+use calc_sim::program::{load_programs, save_programs};
+use calc_sim::units::UnitTable;
+
 fn main() {
     let mut app = App::new();
         app.add_plugins((
@@ -50,24 +57,38 @@ fn main() {
         .init_resource::<CurrentMeshColor>()
         .init_resource::<CountdownCycle>()
         .init_resource::<ScreenAlbedoState>()
-        .insert_resource(SumCurrent::new())
-        .insert_resource(SumVariable::new())
+        .init_resource::<HistoryHovered>()
+        .insert_resource(CalcEngine::new())
         .insert_resource(OpIndex::new())
         .add_systems(Startup, set_window_icon)
         .add_systems(Startup, setup_ui)
         .add_systems(Startup, spawn_gltf)
         .add_systems(Startup, spawn_3d_camera)
         .add_systems(Startup, setup_calc_interface_projection)
+        .add_systems(Startup, load_table::<UnitTable>)
+        .add_systems(Startup, load_table::<RateTable>)
+        .add_systems(Startup, load_table::<ConstantTable>)
+        .add_systems(Startup, load_programs)
         .add_systems(Update, button_animation_system)
         .add_systems(Update, body_animation_system)
         .add_systems(Update, draw_cursor)
         .add_systems(Update, update_sum_text)
         .add_systems(Update, update_var_text)
+        .add_systems(Update, update_status_text)
+        .add_systems(Update, expire_notice)
+        .add_systems(Update, update_rates_text)
+        .add_systems(Update, update_history_text)
+        .add_systems(Update, scroll_history)
+        .add_systems(Update, recall_history)
         .add_systems(Update, handle_asset_events)
+        .add_systems(Update, keyboard_input)
+        .add_systems(Update, run_program)
+        .add_systems(Update, save_programs)
+        .add_systems(Update, error_feedback)
         .add_systems(Update, screen_albedo)
         .add_systems(Update, dim_while_clicked.run_if(|state: Res<ScreenAlbedoState>| state.should_run_dim()))
         .add_systems(Update, cycle_screen_albedo.run_if(|state: Res<ScreenAlbedoState>| state.should_run_cycle()))
-        .add_systems(Update, pan_orbit_camera.run_if(any_with_component::<PanOrbitState>))
+        .add_systems(Update, pan_orbit_camera.after(scroll_history).run_if(any_with_component::<PanOrbitState>))
         .add_systems(Update, release_ray.run_if(input_just_released(MouseButton::Left)))
         .add_systems(Update, fire_ray.run_if(input_pressed(MouseButton::Left)));
         app.run();
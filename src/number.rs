@@ -0,0 +1,176 @@
+use std::fmt;
+
+use crate::complex::Complex;
+use crate::decimal::Decimal;
+use crate::expression::CheckedNeg;
+use crate::fraction::Fraction;
+use crate::{AngleMode, CalcError, PendingOp, UnaryOp};
+
+/// A value held by the engine: a decimal, an exact fraction in fraction mode,
+/// or a complex number in complex mode.
+///
+/// Two fractions stay a fraction for as long as the arithmetic allows;
+/// anything involving a decimal is worked out in decimal. A complex result
+/// with no imaginary part settles back to a decimal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Number {
+    Real(Decimal),
+    Ratio(Fraction),
+    Complex(Complex),
+}
+
+impl Number {
+    pub const ZERO: Number = Number::Real(Decimal::ZERO);
+
+    pub fn exact(value: Decimal) -> Self { // A fraction, unless the decimal is too fine to be one
+        match Fraction::from_decimal(value) {
+            Ok(ratio) => Number::Ratio(ratio),
+            Err(_) => Number::Real(value),
+        }
+    }
+
+    pub fn complex(value: Complex) -> Self { // Real again once the imaginary part cancels
+        if value.is_real() {
+            Number::Real(value.re)
+        } else {
+            Number::Complex(value)
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Real(value) => value.is_zero(),
+            Number::Ratio(ratio) => ratio.numerator() == 0,
+            Number::Complex(value) => value.re.is_zero() && value.im.is_zero(),
+        }
+    }
+
+    pub fn to_decimal(&self, precision: u32) -> Result<Decimal, CalcError> {
+        match self {
+            Number::Real(value) => Ok(*value),
+            Number::Ratio(ratio) => ratio.to_decimal(precision),
+            Number::Complex(value) if value.is_real() => Ok(value.re),
+            Number::Complex(_) => Err(CalcError::Domain), // No real value to give
+        }
+    }
+
+    pub fn to_complex(&self, precision: u32) -> Result<Complex, CalcError> {
+        match self {
+            Number::Complex(value) => Ok(*value),
+            _ => Ok(Complex::from(self.to_decimal(precision)?)),
+        }
+    }
+
+    pub fn to_integer(&self) -> Result<i128, CalcError> {
+        match self {
+            Number::Real(value) => value.to_integer(),
+            Number::Ratio(ratio) if ratio.is_integer() => Ok(ratio.numerator()),
+            Number::Ratio(_) => Err(CalcError::Domain),
+            Number::Complex(_) => self.to_decimal(0)?.to_integer(),
+        }
+    }
+
+    pub fn trunc(&self) -> Self {
+        match self {
+            Number::Real(value) => Number::Real(value.trunc()),
+            Number::Ratio(ratio) => Number::Real(Decimal::new(ratio.trunc(), 0)),
+            Number::Complex(value) => Number::Real(value.re.trunc()), // The real part
+        }
+    }
+
+    pub fn to_mixed(&self) -> String { // Fractions as mixed numbers, decimals unchanged
+        match self {
+            Number::Ratio(ratio) => ratio.to_mixed(),
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn apply(op: PendingOp, lhs: Number, rhs: Number, precision: u32) -> Result<Number, CalcError> {
+        if let (Number::Complex(_), _) | (_, Number::Complex(_)) = (lhs, rhs) {
+            let lhs: Complex = lhs.to_complex(precision)?;
+            return match lhs.apply(op, rhs.to_complex(precision)?, precision) {
+                Some(res) => res.map(Number::complex),
+                None => Err(CalcError::Domain),
+            };
+        }
+        if let (Number::Ratio(lhs), Number::Ratio(rhs)) = (lhs, rhs) {
+            match lhs.apply(op, rhs) {
+                None | Some(Err(CalcError::Overflow)) => {}, // Not exact, so answer in decimal
+                Some(res) => return res.map(Number::Ratio),
+            }
+        }
+        let lhs: Decimal = lhs.to_decimal(precision)?;
+        let rhs: Decimal = rhs.to_decimal(precision)?;
+        Ok(Number::Real(op.apply(lhs, rhs, precision)?))
+    }
+
+    pub fn unary(op: UnaryOp, value: Number, precision: u32, angle_mode: AngleMode) -> Result<Number, CalcError> {
+        if let Number::Complex(z) = value {
+            return match op {
+                UnaryOp::Square => z.checked_mul(z, precision).map(Number::complex),
+                UnaryOp::Reciprocal => Complex::from(Decimal::ONE).checked_div(z, precision).map(Number::complex),
+                UnaryOp::SquareRoot => z.sqrt(precision).map(Number::complex),
+                UnaryOp::Magnitude => z.magnitude(precision).map(Number::Real),
+                UnaryOp::Argument => z.argument(precision, angle_mode).map(Number::Real),
+                UnaryOp::Conjugate => z.conj().map(Number::complex),
+                _ if z.is_real() => Number::unary(op, Number::Real(z.re), precision, angle_mode),
+                _ => Err(CalcError::Domain),
+            };
+        }
+        if let Number::Ratio(ratio) = value {
+            let exact = match op {
+                UnaryOp::Square => ratio.checked_mul(ratio).ok(),
+                UnaryOp::Reciprocal => Some(ratio.recip()?),
+                UnaryOp::SquareRoot => ratio.sqrt(),
+                _ => None,
+            };
+            if let Some(res) = exact {
+                return Ok(Number::Ratio(res));
+            }
+        }
+        let value: Decimal = value.to_decimal(precision)?;
+        Ok(Number::Real(op.apply(value, precision, angle_mode)?))
+    }
+}
+
+impl Default for Number {
+    fn default() -> Self {
+        Number::ZERO
+    }
+}
+
+impl From<Decimal> for Number {
+    fn from(value: Decimal) -> Self {
+        Number::Real(value)
+    }
+}
+
+impl CheckedNeg for Number {
+    fn checked_neg(&self) -> Result<Number, CalcError> {
+        match self {
+            Number::Real(value) => value.checked_neg().map(Number::Real),
+            Number::Ratio(ratio) => ratio.checked_neg().map(Number::Ratio),
+            Number::Complex(value) => value.checked_neg().map(Number::Complex),
+        }
+    }
+}
+
+impl PartialEq<f64> for Number {
+    fn eq(&self, other: &f64) -> bool {
+        match self {
+            Number::Real(value) => value == other,
+            Number::Ratio(ratio) => Decimal::from_f64(*other).and_then(Fraction::from_decimal) == Ok(*ratio),
+            Number::Complex(value) => value.is_real() && value.re == *other,
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Number::Real(value) => write!(f, "{}", value),
+            Number::Ratio(ratio) => write!(f, "{}", ratio),
+            Number::Complex(value) => write!(f, "{}", value),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use bevy::prelude::*;
+
+use crate::calculator::CalcButtons;
+use crate::number::Number;
+use crate::{CalcEngine, CalcError};
+
+pub const PROGRAMS_PATH: &str = "assets/programs.txt"; // Defaults shipped with the app, read when nothing has been saved
+pub const USER_PROGRAMS_PATH: &str = "user/programs.txt"; // Where recording saves, outside assets and ignored by git
+pub const PROGRAM_SLOTS: usize = 10; // Picked with the digit keys, 0 to 9
+pub const PROGRAM_STEPS: usize = 99; // Step numbers stay two digits on the screen
+pub const RUN_LIMIT: usize = 10_000; // Steps a run takes before it pauses, so an endless GTO loop can be stopped
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition { // Tests on the value showing, the next step is skipped when false
+    Zero,
+    NotZero,
+    Positive,
+    Negative,
+}
+
+impl Condition {
+    pub fn from_digit(digit: i32) -> Option<Self> {
+        match digit {
+            1 => Some(Condition::Zero),
+            2 => Some(Condition::NotZero),
+            3 => Some(Condition::Positive),
+            4 => Some(Condition::Negative),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Condition::Zero => "x=0?",
+            Condition::NotZero => "x≠0?",
+            Condition::Positive => "x>0?",
+            Condition::Negative => "x<0?",
+        }
+    }
+
+    pub fn holds(&self, x: Number, precision: u32) -> bool { // A complex x is neither positive nor negative
+        match self {
+            Condition::Zero => x.is_zero(),
+            Condition::NotZero => !x.is_zero(),
+            Condition::Positive => x.to_decimal(precision).is_ok_and(|x| !x.is_zero() && !x.is_negative()),
+            Condition::Negative => x.to_decimal(precision).is_ok_and(|x| x.is_negative()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Key(CalcButtons), // Pressed as if from the keypad
+    Label(i32),
+    Goto(i32),        // To the label of the same number, in the same program
+    Test(Condition),
+    Stop,             // R/S, pauses the run
+}
+
+impl Step {
+    pub fn label(&self) -> String { // Also how the step is written to disk
+        match self {
+            Step::Key(button) => format!("{:?}", button),
+            Step::Label(label) => format!("LBL {}", label),
+            Step::Goto(label) => format!("GTO {}", label),
+            Step::Test(condition) => condition.label().to_string(),
+            Step::Stop => "R/S".to_string(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let digit = |text: &str| text.trim().parse::<i32>().ok().filter(|digit| (0..10).contains(digit));
+        if let Some(label) = text.strip_prefix("LBL ") {
+            return digit(label).map(Step::Label);
+        }
+        if let Some(label) = text.strip_prefix("GTO ") {
+            return digit(label).map(Step::Goto);
+        }
+        if text == "R/S" {
+            return Some(Step::Stop);
+        }
+        match (1..5).filter_map(Condition::from_digit).find(|condition| condition.label() == text) {
+            Some(condition) => Some(Step::Test(condition)),
+            None => CalcButtons::from_name(text).map(Step::Key),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramWait { // A programming key waiting on its digit
+    Record,  // PRGM, the program to record
+    Execute, // XEQ, the program to run
+    Label,
+    Goto,
+    Test,    // 1 x=0?, 2 x≠0?, 3 x>0?, 4 x<0?
+}
+
+impl ProgramWait {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProgramWait::Record => "PRGM _",
+            ProgramWait::Execute => "XEQ _",
+            ProgramWait::Label => "LBL _",
+            ProgramWait::Goto => "GTO _",
+            ProgramWait::Test => "x? 1-4",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunState {
+    Running,
+    Stepping, // SST, one step then paused
+    Paused,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub next: usize,  // The step to take next
+    pub state: RunState,
+    pub taken: usize, // Steps since the last R/S, toward RUN_LIMIT
+}
+
+/// The keystroke programs, and the one being recorded or run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Programs {
+    pub slots: Vec<Vec<Step>>,
+    pub selected: usize, // The program R/S and SST run
+    pub recording: bool,
+    pub waiting: Option<ProgramWait>,
+    pub run: Option<Run>,
+    pub unsaved: bool, // Recorded since the last write to disk
+}
+
+impl Programs {
+    pub fn new() -> Self {
+        Programs {
+            slots: vec![Vec::new(); PROGRAM_SLOTS],
+            selected: 0,
+            recording: false,
+            waiting: None,
+            run: None,
+            unsaved: false,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text: String = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        Programs::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> { // Lines of slot,step,step,..., '#' for comments
+        let mut programs = Programs::new();
+        for (number, line) in text.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            let bad = |what: &str| format!("line {}: {}", number + 1, what);
+            let slot: usize = match fields[0].parse::<usize>() {
+                Ok(slot) if slot < PROGRAM_SLOTS => slot,
+                _ => return Err(bad("program is not 0 to 9")),
+            };
+            if fields.len() - 1 > PROGRAM_STEPS {
+                return Err(bad("too many steps"));
+            }
+            programs.slots[slot] = fields[1..].iter()
+                .map(|step| Step::parse(step).ok_or_else(|| bad(&format!("unknown step {}", step))))
+                .collect::<Result<Vec<Step>, String>>()?;
+        }
+        Ok(programs)
+    }
+
+    pub fn to_text(&self) -> String { // Empty programs are left out
+        let mut text: String = String::from("# Keystroke programs, one per line as program,step,step,...\n");
+        for (slot, steps) in self.slots.iter().enumerate().filter(|(_, steps)| !steps.is_empty()) {
+            let steps: Vec<String> = steps.iter().map(Step::label).collect();
+            text += &format!("{},{}\n", slot, steps.join(","));
+        }
+        text
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir).map_err(|err| format!("{}: {}", path, err))?;
+        }
+        std::fs::write(path, self.to_text()).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn running(&self) -> bool { // Stepping counts, the key it takes is on its way
+        matches!(self.run, Some(Run { state: RunState::Running | RunState::Stepping, .. }))
+    }
+
+    pub fn record(&mut self, step: Step) {
+        let steps: &mut Vec<Step> = &mut self.slots[self.selected];
+        if steps.len() >= PROGRAM_STEPS {
+            info!("Program {} is full at {} steps", self.selected, PROGRAM_STEPS);
+            return;
+        }
+        steps.push(step);
+        self.unsaved = true;
+    }
+
+    pub fn unrecord(&mut self) { // Backspace while recording
+        if self.slots[self.selected].pop().is_some() {
+            self.unsaved = true;
+        }
+    }
+
+    pub fn complete(&mut self, wait: ProgramWait, digit: i32) { // The digit a waiting key was after
+        self.waiting = None;
+        match wait {
+            ProgramWait::Record => {
+                self.selected = digit as usize;
+                self.slots[self.selected].clear(); // Recorded over, as on the classic programmables
+                self.recording = true;
+                self.run = None;
+                self.unsaved = true;
+            },
+            ProgramWait::Execute => {
+                self.selected = digit as usize;
+                self.start(RunState::Running);
+            },
+            ProgramWait::Label => self.record(Step::Label(digit)),
+            ProgramWait::Goto => self.record(Step::Goto(digit)),
+            ProgramWait::Test => match Condition::from_digit(digit) {
+                Some(condition) => self.record(Step::Test(condition)),
+                None => {
+                    info!("Test: 1 x=0?, 2 x≠0?, 3 x>0? or 4 x<0?");
+                    self.waiting = Some(wait);
+                },
+            },
+        }
+    }
+
+    fn start(&mut self, state: RunState) {
+        self.run = Some(Run { next: 0, state, taken: 0 });
+    }
+
+    pub fn run_stop(&mut self) { // R/S: runs from the top, pauses a run, or carries on from a pause
+        match self.run.as_mut() {
+            None => self.start(RunState::Running),
+            Some(run) if run.state == RunState::Paused => {
+                run.state = RunState::Running;
+                run.taken = 0;
+            },
+            Some(run) => run.state = RunState::Paused,
+        }
+    }
+
+    pub fn single_step(&mut self) { // SST: one step, from the top if nothing is running
+        match self.run.as_mut() {
+            None => self.start(RunState::Stepping),
+            Some(run) => run.state = RunState::Stepping,
+        }
+    }
+
+    pub fn next_key(&mut self, x: Number, precision: u32) -> Result<Option<CalcButtons>, CalcError> {
+        // Works through the program up to its next key, which the caller presses
+        let steps: &Vec<Step> = &self.slots[self.selected];
+        let Some(run) = self.run.as_mut() else {
+            return Ok(None);
+        };
+        while run.state != RunState::Paused {
+            let Some(step) = steps.get(run.next) else {
+                info!("Program {} done", self.selected);
+                self.run = None;
+                return Ok(None);
+            };
+            if run.taken >= RUN_LIMIT {
+                info!("Program {} paused after {} steps, R/S carries on", self.selected, RUN_LIMIT);
+                run.state = RunState::Paused;
+                return Ok(None);
+            }
+            run.next += 1;
+            run.taken += 1;
+            if run.state == RunState::Stepping {
+                run.state = RunState::Paused;
+            }
+            match step {
+                Step::Key(button) => return Ok(Some(button.clone())),
+                Step::Label(_) => {},
+                Step::Goto(label) => match steps.iter().position(|step| *step == Step::Label(*label)) {
+                    Some(index) => run.next = index + 1,
+                    None => {
+                        self.run = None;
+                        return Err(CalcError::InvalidInput); // No such label
+                    },
+                },
+                Step::Test(condition) => {
+                    if !condition.holds(x, precision) {
+                        run.next += 1;
+                    }
+                },
+                Step::Stop => run.state = RunState::Paused,
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn line(&self) -> Option<String> { // The input line while recording or waiting, such as "1-03 Add"
+        if let Some(wait) = self.waiting {
+            return Some(wait.label().to_string());
+        }
+        if !self.recording {
+            return None;
+        }
+        let steps: &Vec<Step> = &self.slots[self.selected];
+        Some(match steps.last() {
+            Some(step) => format!("{}-{:02} {}", self.selected, steps.len(), step.label()),
+            None => format!("{}-00", self.selected),
+        })
+    }
+
+    pub fn status(&self) -> Option<String> { // "PRGM 1" recording, "RUN 1" running, "1-04" paused on step 4
+        if self.recording {
+            return Some(format!("PRGM {}", self.selected));
+        }
+        match self.run {
+            Some(Run { state: RunState::Paused, next, .. }) => Some(format!("{}-{:02}", self.selected, next + 1)),
+            Some(_) => Some(format!("RUN {}", self.selected)),
+            None => None,
+        }
+    }
+}
+
+impl Default for Programs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn load_programs(
+    mut engine: ResMut<CalcEngine>,
+) {
+    match Programs::load(USER_PROGRAMS_PATH).or_else(|_| Programs::load(PROGRAMS_PATH)) {
+        Ok(programs) => {
+            info!("Programs loaded: {}", programs.slots.iter().filter(|steps| !steps.is_empty()).count());
+            engine.programs = programs;
+        },
+        Err(err) => info!("No programs loaded, recording saves them: {}", err),
+    }
+}
+
+pub fn save_programs(
+    mut engine: ResMut<CalcEngine>,
+) {
+    if !engine.programs.unsaved || engine.programs.recording {
+        return; // Written once recording ends, not each step
+    }
+    engine.programs.unsaved = false;
+    match engine.programs.save(USER_PROGRAMS_PATH) {
+        Ok(()) => info!("Programs saved to {}", USER_PROGRAMS_PATH),
+        Err(err) => warn!("Programs not saved: {}", err),
+    }
+}
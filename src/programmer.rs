@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use crate::calculator::CalcButtons;
+use crate::{CalcEngine, CalcError, PendingOp};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base {
+    Hex,
+    Dec,
+    Oct,
+    Bin,
+}
+
+impl Base {
+    pub fn radix(&self) -> u32 {
+        match self {
+            Base::Hex => 16,
+            Base::Dec => 10,
+            Base::Oct => 8,
+            Base::Bin => 2,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Base::Hex => Base::Dec,
+            Base::Dec => Base::Oct,
+            Base::Oct => Base::Bin,
+            Base::Bin => Base::Hex,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Base::Hex => "HEX",
+            Base::Dec => "DEC",
+            Base::Oct => "OCT",
+            Base::Bin => "BIN",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordSize {
+    Bits8,
+    Bits16,
+    Bits32,
+    Bits64,
+}
+
+impl WordSize {
+    pub fn bits(&self) -> u32 {
+        match self {
+            WordSize::Bits8 => 8,
+            WordSize::Bits16 => 16,
+            WordSize::Bits32 => 32,
+            WordSize::Bits64 => 64,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            WordSize::Bits8 => WordSize::Bits16,
+            WordSize::Bits16 => WordSize::Bits32,
+            WordSize::Bits32 => WordSize::Bits64,
+            WordSize::Bits64 => WordSize::Bits8,
+        }
+    }
+}
+
+/// How programmer mode stores and shows its integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntFormat {
+    pub base: Base,
+    pub word: WordSize,
+    pub signed: bool,
+}
+
+impl IntFormat {
+    pub fn new() -> Self {
+        IntFormat {
+            base: Base::Dec,
+            word: WordSize::Bits64,
+            signed: true,
+        }
+    }
+
+    /// Truncates to the word through the native integer types, so it wraps as the hardware would.
+    pub fn wrap(&self, value: i128) -> i128 {
+        match (self.word, self.signed) {
+            (WordSize::Bits8, true) => value as i8 as i128,
+            (WordSize::Bits8, false) => value as u8 as i128,
+            (WordSize::Bits16, true) => value as i16 as i128,
+            (WordSize::Bits16, false) => value as u16 as i128,
+            (WordSize::Bits32, true) => value as i32 as i128,
+            (WordSize::Bits32, false) => value as u32 as i128,
+            (WordSize::Bits64, true) => value as i64 as i128,
+            (WordSize::Bits64, false) => value as u64 as i128,
+        }
+    }
+
+    pub fn apply(&self, op: PendingOp, lhs: i128, rhs: i128) -> Result<i128, CalcError> {
+        let bits: i128 = self.word.bits() as i128;
+        let res: i128 = match op {
+            PendingOp::Add => lhs.wrapping_add(rhs),
+            PendingOp::Subtract => lhs.wrapping_sub(rhs),
+            PendingOp::Multiply => lhs.wrapping_mul(rhs), // The low bits survive the i128 wrap
+            PendingOp::Divide => lhs.checked_div(rhs).ok_or(CalcError::DivideByZero)?, // Truncates toward zero
+            PendingOp::Modulo => lhs.checked_rem(rhs).ok_or(CalcError::DivideByZero)?,
+            PendingOp::Power => {
+                let exp: u32 = u32::try_from(rhs).map_err(|_| CalcError::Domain)?;
+                lhs.wrapping_pow(exp)
+            },
+            PendingOp::And => lhs & rhs,
+            PendingOp::Or => lhs | rhs,
+            PendingOp::Xor => lhs ^ rhs,
+            PendingOp::ShiftLeft if rhs < 0 => return Err(CalcError::Domain),
+            PendingOp::ShiftLeft if rhs >= bits => 0,
+            PendingOp::ShiftLeft => lhs << rhs,
+            PendingOp::ShiftRight if rhs < 0 => return Err(CalcError::Domain),
+            PendingOp::ShiftRight => lhs >> rhs.min(bits), // Arithmetic when signed, the value is never negative when not
+            PendingOp::Markup | PendingOp::PercentChange => return Err(CalcError::Domain), // Percentages, not integer arithmetic
+        };
+        Ok(self.wrap(res))
+    }
+
+    pub fn format(&self, value: i128) -> String { // Hex, octal and binary show the raw two's complement bits
+        let bits: u128 = value as u128 & (u128::MAX >> (128 - self.word.bits()));
+        match self.base {
+            Base::Hex => format!("{:X}", bits),
+            Base::Dec => self.wrap(value).to_string(),
+            Base::Oct => format!("{:o}", bits),
+            Base::Bin => format!("{:b}", bits),
+        }
+    }
+
+    pub fn max_digits(&self) -> usize { // Digits the widest word takes in the active base
+        let mut max: u128 = u128::MAX >> (128 - self.word.bits());
+        let mut digits: usize = 0;
+        while max > 0 {
+            max /= self.base.radix() as u128;
+            digits += 1;
+        }
+        digits
+    }
+
+    pub fn label(&self) -> String { // Shown on the device screen, such as "HEX i32"
+        let sign = if self.signed { "i" } else { "u" };
+        format!("{} {}{}", self.base.label(), sign, self.word.bits())
+    }
+}
+
+impl Default for IntFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalcEngine {
+    pub(crate) fn handle_programmer(&mut self, button: CalcButtons) -> bool { // The word format keys, and whole numbers only
+        match button {
+            CalcButtons::Decimal => {
+                info!("Decimal: programmer mode only takes whole numbers");
+            },
+            CalcButtons::Percent => {
+                info!("Percent: programmer mode only takes whole numbers");
+            },
+            CalcButtons::Base => {
+                let format = IntFormat { base: self.int_format.base.next(), ..self.int_format };
+                self.set_int_format(format);
+            },
+            CalcButtons::WordSize => {
+                let format = IntFormat { word: self.int_format.word.next(), ..self.int_format };
+                self.set_int_format(format);
+            },
+            CalcButtons::Signed => {
+                let format = IntFormat { signed: !self.int_format.signed, ..self.int_format };
+                self.set_int_format(format);
+            },
+            _ => return false,
+        }
+        true
+    }
+}
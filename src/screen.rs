@@ -0,0 +1,45 @@
+use crate::decimal::Decimal;
+use crate::{CalcError, CalcMode};
+
+pub const SUM_WIDTH: usize = 8;    // Characters the device screen has after "Sum: ", and for RPN's X
+pub const INPUT_WIDTH: usize = 6;  // After "Input: "
+pub const PART_WIDTH: usize = 7;   // Each line of a complex value
+pub const STACK_WIDTH: usize = 14; // Y, Z and T, in the smaller stack font
+pub const LINE_WIDTH: usize = 13;  // A browsed line, as wide as "Input: " and six digits
+
+/// The two lines of the device screen, fitted to its width.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Screen {
+    pub input: String,
+    pub sum: String,
+    pub stacked: bool, // The input line holds the RPN stack, in a smaller font
+}
+
+impl Screen {
+    pub fn error(error: CalcError) -> Self {
+        Screen {
+            input: "Input: E".to_string(),
+            sum: "E: ".to_string() + error.label(),
+            stacked: false,
+        }
+    }
+}
+
+pub fn fit_digits(text: &str, width: usize) -> String { // Programmer values wrap rather than overflow, so keep the low digits
+    if text.len() <= width {
+        return text.to_owned();
+    }
+    "<".to_owned() + &text[text.len() + 1 - width..]
+}
+
+pub fn fit_value(text: &str, width: usize, mode: CalcMode) -> Result<String, CalcError> { // One value of the RPN stack
+    match mode {
+        CalcMode::Programmer => Ok(fit_digits(text, width)),
+        _ if text.len() <= width => Ok(text.to_owned()),
+        _ => text.parse::<Decimal>().map_err(|_| CalcError::Overflow).and_then(|value| value.to_width(width)),
+    }
+}
+
+pub fn fit_line(line: &str) -> String {
+    line.chars().take(LINE_WIDTH).collect()
+}
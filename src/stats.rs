@@ -0,0 +1,292 @@
+use crate::calculator::CalcButtons;
+use crate::decimal::{Decimal, GUARD_DIGITS, MAX_PRECISION};
+use crate::number::Number;
+use crate::{sum_calc_operations, CalcEngine, CalcError, CalcOperations, EntryMode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub x: Decimal,
+    pub y: Option<Decimal>, // Paired data, for regression
+}
+
+impl Sample {
+    pub fn to_display(&self) -> String {
+        match self.y {
+            Some(y) => format!("{},{}", self.x, y),
+            None => self.x.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Statistic {
+    Count,
+    Mean,
+    SampleSd,     // s, dividing by n - 1
+    PopulationSd, // σ, dividing by n
+    Sum,
+    SumSquares,
+    Slope,        // Of the least squares line y = slope·x + intercept
+    Intercept,
+    Correlation,  // r
+}
+
+/// The samples entered with Σ+ in statistics mode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub samples: Vec<Sample>,
+    pub pending_x: Option<Decimal>, // x of a pair, waiting on its y
+    pub review: Option<usize>,      // The sample being looked at, and replaced or removed by Σ+ and Σ-
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    pub fn add(&mut self, sample: Sample) { // Replaces the sample under review instead, if there is one
+        match self.review.take() {
+            Some(index) if index < self.samples.len() => self.samples[index] = sample,
+            _ => self.samples.push(sample),
+        }
+    }
+
+    pub fn remove(&mut self, sample: Sample) -> Result<(), CalcError> {
+        // Under review the sample looked at goes, otherwise the last one entered that matches
+        let index: usize = match self.review {
+            Some(index) if index < self.samples.len() => index,
+            _ => self.samples.iter().rposition(|entered| *entered == sample).ok_or(CalcError::InvalidInput)?,
+        };
+        self.samples.remove(index);
+        if self.samples.is_empty() {
+            self.review = None;
+        } else if let Some(index) = self.review {
+            self.review = Some(index.min(self.samples.len() - 1));
+        }
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Stats::new();
+    }
+
+    pub fn review_next(&mut self) { // Steps off the end back to entry
+        self.review = match self.review {
+            None if !self.samples.is_empty() => Some(0),
+            Some(index) if index + 1 < self.samples.len() => Some(index + 1),
+            _ => None,
+        };
+    }
+
+    pub fn review_prev(&mut self) {
+        self.review = match self.review {
+            None => self.samples.len().checked_sub(1),
+            Some(index) => index.checked_sub(1),
+        };
+    }
+
+    pub fn label(&self) -> String { // "n=5", "#2/5" under review, "XY" once x of a pair is in
+        let mut label: String = match self.review {
+            Some(index) => format!("#{}/{}", index + 1, self.samples.len()),
+            None => format!("n={}", self.samples.len()),
+        };
+        if self.pending_x.is_some() {
+            label += " XY";
+        }
+        label
+    }
+
+    pub fn reviewing(&self) -> Option<String> { // "#2 4.5,3" for the second sample
+        let index: usize = self.review?;
+        self.samples.get(index).map(|sample| format!("#{} {}", index + 1, sample.to_display()))
+    }
+
+    pub fn get(&self, stat: Statistic, precision: u32) -> Result<Decimal, CalcError> {
+        let working: u32 = (precision + GUARD_DIGITS).min(MAX_PRECISION);
+        let res: Decimal = match stat {
+            Statistic::Count => Decimal::from(self.samples.len()),
+            Statistic::Sum => self.sum(|sample| Ok(sample.x), working)?,
+            Statistic::SumSquares => self.sum(|sample| sample.x.checked_mul(sample.x, working), working)?,
+            Statistic::Mean => self.sum(|sample| Ok(sample.x), working)?.checked_div(self.count(1)?, working)?,
+            Statistic::SampleSd => sqrt(self.sxx(working)?.checked_div(self.count(2)?.checked_sub(Decimal::ONE)?, working)?, working)?,
+            Statistic::PopulationSd => sqrt(self.sxx(working)?.checked_div(self.count(1)?, working)?, working)?,
+            Statistic::Slope => self.slope(working)?,
+            Statistic::Intercept => {
+                let (mean_x, mean_y): (Decimal, Decimal) = self.means(working)?;
+                mean_y.checked_sub(self.slope(working)?.checked_mul(mean_x, working)?)?
+            },
+            Statistic::Correlation => {
+                let spread: Decimal = sqrt(self.sxx(working)?.checked_mul(self.syy(working)?, working)?, working)?;
+                if spread.is_zero() {
+                    return Err(CalcError::Domain); // A flat line in x or y has no correlation
+                }
+                self.sxy(working)?.checked_div(spread, working)?
+            },
+        };
+        res.round(precision)
+    }
+
+    fn count(&self, min: usize) -> Result<Decimal, CalcError> { // Too few samples for the statistic is a domain error
+        if self.samples.len() < min {
+            return Err(CalcError::Domain);
+        }
+        Ok(Decimal::from(self.samples.len()))
+    }
+
+    fn sum<F>(&self, term: F, precision: u32) -> Result<Decimal, CalcError>
+    where
+        F: Fn(&Sample) -> Result<Decimal, CalcError>,
+    {
+        let mut total: Decimal = Decimal::ZERO;
+        for sample in &self.samples {
+            total = total.checked_add(term(sample)?.round(precision)?)?;
+        }
+        Ok(total)
+    }
+
+    fn paired_y(sample: &Sample) -> Result<Decimal, CalcError> {
+        sample.y.ok_or(CalcError::Domain) // Regression needs every sample paired
+    }
+
+    fn means(&self, precision: u32) -> Result<(Decimal, Decimal), CalcError> {
+        let n: Decimal = self.count(2)?;
+        let mean_x: Decimal = self.sum(|sample| Ok(sample.x), precision)?.checked_div(n, precision)?;
+        let mean_y: Decimal = self.sum(Stats::paired_y, precision)?.checked_div(n, precision)?;
+        Ok((mean_x, mean_y))
+    }
+
+    // Sums of squared deviations from the mean, Σ(x - x̄)², Σ(y - ȳ)² and Σ(x - x̄)(y - ȳ)
+    fn sxx(&self, precision: u32) -> Result<Decimal, CalcError> {
+        let mean: Decimal = self.sum(|sample| Ok(sample.x), precision)?.checked_div(self.count(1)?, precision)?;
+        self.sum(|sample| {
+            let dx: Decimal = sample.x.checked_sub(mean)?;
+            dx.checked_mul(dx, precision)
+        }, precision)
+    }
+
+    fn syy(&self, precision: u32) -> Result<Decimal, CalcError> {
+        let (_, mean_y): (Decimal, Decimal) = self.means(precision)?;
+        self.sum(|sample| {
+            let dy: Decimal = Stats::paired_y(sample)?.checked_sub(mean_y)?;
+            dy.checked_mul(dy, precision)
+        }, precision)
+    }
+
+    fn sxy(&self, precision: u32) -> Result<Decimal, CalcError> {
+        let (mean_x, mean_y): (Decimal, Decimal) = self.means(precision)?;
+        self.sum(|sample| {
+            let dy: Decimal = Stats::paired_y(sample)?.checked_sub(mean_y)?;
+            sample.x.checked_sub(mean_x)?.checked_mul(dy, precision)
+        }, precision)
+    }
+
+    fn slope(&self, precision: u32) -> Result<Decimal, CalcError> {
+        let sxx: Decimal = self.sxx(precision)?;
+        if sxx.is_zero() {
+            return Err(CalcError::Domain); // Every x the same, the line is vertical
+        }
+        self.sxy(precision)?.checked_div(sxx, precision)
+    }
+}
+
+const SQRT_STEPS: usize = 64; // Newton's method settles in a handful, this only stops a rounding see-saw
+
+fn sqrt(value: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+    if value.is_negative() {
+        return Err(CalcError::Domain);
+    }
+    if value.is_zero() {
+        return Ok(Decimal::ZERO);
+    }
+    let mut root: Decimal = Decimal::from_f64(value.to_f64().sqrt())?; // Close from f64, Newton's method finishes the digits
+    if root.is_zero() {
+        root = Decimal::ONE;
+    }
+    for _ in 0..SQRT_STEPS {
+        let next: Decimal = root.checked_add(value.checked_div(root, precision)?)?.checked_div(Decimal::from(2), precision)?;
+        if next == root {
+            break;
+        }
+        root = next;
+    }
+    Ok(root)
+}
+
+impl CalcEngine {
+    pub(crate) fn handle_stats(&mut self, button: CalcButtons) -> bool { // Σ+ and the statistics, in statistics mode
+        match button {
+            CalcButtons::StatAdd => sum_calc_operations(self, CalcOperations::StatAdd),
+            CalcButtons::StatRemove => sum_calc_operations(self, CalcOperations::StatRemove),
+            CalcButtons::StatPair => self.stat_pair(),
+            CalcButtons::StatReviewNext => self.stats.review_next(),
+            CalcButtons::StatReviewPrev => self.stats.review_prev(),
+            CalcButtons::StatClear => self.stats.clear(),
+            CalcButtons::StatCount => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Count)),
+            CalcButtons::StatMean => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Mean)),
+            CalcButtons::StatSampleSd => sum_calc_operations(self, CalcOperations::Statistic(Statistic::SampleSd)),
+            CalcButtons::StatPopulationSd => sum_calc_operations(self, CalcOperations::Statistic(Statistic::PopulationSd)),
+            CalcButtons::StatSum => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Sum)),
+            CalcButtons::StatSumSquares => sum_calc_operations(self, CalcOperations::Statistic(Statistic::SumSquares)),
+            CalcButtons::StatSlope => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Slope)),
+            CalcButtons::StatIntercept => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Intercept)),
+            CalcButtons::StatCorrelation => sum_calc_operations(self, CalcOperations::Statistic(Statistic::Correlation)),
+            _ => return false,
+        }
+        true
+    }
+
+    fn stat_input(&self) -> Result<Decimal, CalcError> {
+        self.showing().to_decimal(self.sum.precision)
+    }
+
+    fn stat_pair(&mut self) { // x of an x,y pair, the y is typed next
+        match self.stat_input() {
+            Ok(x) => {
+                self.rpn_fold();
+                self.stats.pending_x = Some(x);
+                self.sum.sum = Number::Real(x);
+                self.index = CalcOperations::Sum;
+                self.stack_lift = true; // In RPN the y typed next pushes x to Y
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub(crate) fn stat_update(&mut self, remove: bool) { // Σ+ and Σ-
+        if self.stats.review.is_some() && !self.has_entry() && self.stats.pending_x.is_none() {
+            // Reviewing with nothing typed: Σ+ steps on, Σ- drops the sample looked at
+            if !remove {
+                self.stats.review_next();
+            } else if let Err(error) = self.stats.remove(Sample { x: Decimal::ZERO, y: None }) {
+                self.error = Some(error);
+            }
+            return;
+        }
+        let value: Decimal = match self.stat_input() {
+            Ok(value) => value,
+            Err(error) => {
+                self.error = Some(error);
+                return;
+            },
+        };
+        let sample: Sample = match self.stats.pending_x.take() {
+            Some(x) => Sample { x, y: Some(value) },
+            None => Sample { x: value, y: None },
+        };
+        if remove {
+            if let Err(error) = self.stats.remove(sample) {
+                self.error = Some(error);
+                return;
+            }
+        } else {
+            self.stats.add(sample);
+        }
+        // The count shows, and like ENTER the next number typed replaces it
+        if self.entry_mode == EntryMode::Rpn {
+            self.rpn_fold();
+            self.stack_lift = false; // X becomes the count rather than lifting
+        }
+        self.show_result(Number::Real(Decimal::from(self.stats.samples.len())));
+        self.stack_lift = false;
+    }
+}
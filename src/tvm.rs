@@ -0,0 +1,362 @@
+use bevy::prelude::*;
+
+use crate::calculator::CalcButtons;
+use crate::decimal::Decimal;
+use crate::number::Number;
+use crate::{sum_calc_operations, CalcEngine, CalcError, CalcOperations};
+
+const SOLVER_STEPS: usize = 100; // Newton iterations per starting guess
+const SOLVER_GUESSES: [f64; 5] = [0.01, 0.1, 0.001, 0.5, -0.5]; // Periodic rates the solver starts from
+const SOLVER_TOLERANCE: f64 = 1e-12;
+const F64_DIGITS: i32 = 14; // Significant digits an f64 result is trusted to
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TvmVar {
+    Periods,      // N
+    Rate,         // I/Y, the yearly rate in percent
+    PresentValue, // PV
+    Payment,      // PMT
+    FutureValue,  // FV
+}
+
+impl TvmVar {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TvmVar::Periods => "N",
+            TvmVar::Rate => "I/Y",
+            TvmVar::PresentValue => "PV",
+            TvmVar::Payment => "PMT",
+            TvmVar::FutureValue => "FV",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmortField { // The figure the amortization viewer shows for its period
+    Interest,
+    Principal,
+    Balance,
+}
+
+impl AmortField {
+    pub fn next(&self) -> Self {
+        match self {
+            AmortField::Interest => AmortField::Principal,
+            AmortField::Principal => AmortField::Balance,
+            AmortField::Balance => AmortField::Interest,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AmortField::Interest => "INT",
+            AmortField::Principal => "PRN",
+            AmortField::Balance => "BAL",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AmortRow {
+    pub period: u32,
+    pub interest: Decimal,  // Signed as the payment is, so interest paid on a loan is negative
+    pub principal: Decimal, // The rest of the payment
+    pub balance: Decimal,   // Left once the period is over
+}
+
+impl AmortRow {
+    pub fn get(&self, field: AmortField) -> Decimal {
+        match field {
+            AmortField::Interest => self.interest,
+            AmortField::Principal => self.principal,
+            AmortField::Balance => self.balance,
+        }
+    }
+}
+
+/// Time value of money: cash in positive and cash out negative, so a loan
+/// taken has a positive PV and negative payments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tvm {
+    pub periods: Decimal,
+    pub rate: Decimal,
+    pub present_value: Decimal,
+    pub payment: Decimal,
+    pub future_value: Decimal,
+    pub per_year: u32, // P/Y, payments a year, which the yearly rate is divided by
+    pub begin: bool,   // Payments at the start of each period, rather than the end
+    pub last: Option<TvmVar>, // Stored or solved last, for the status line
+    pub amort: Option<(u32, AmortField)>, // The period and figure the viewer is on, while it is open
+}
+
+impl Tvm {
+    pub fn new() -> Self {
+        Tvm {
+            periods: Decimal::ZERO,
+            rate: Decimal::ZERO,
+            present_value: Decimal::ZERO,
+            payment: Decimal::ZERO,
+            future_value: Decimal::ZERO,
+            per_year: 12,
+            begin: false,
+            last: None,
+            amort: None,
+        }
+    }
+
+    pub fn get(&self, var: TvmVar) -> Decimal {
+        match var {
+            TvmVar::Periods => self.periods,
+            TvmVar::Rate => self.rate,
+            TvmVar::PresentValue => self.present_value,
+            TvmVar::Payment => self.payment,
+            TvmVar::FutureValue => self.future_value,
+        }
+    }
+
+    pub fn set(&mut self, var: TvmVar, value: Decimal) {
+        match var {
+            TvmVar::Periods => self.periods = value,
+            TvmVar::Rate => self.rate = value,
+            TvmVar::PresentValue => self.present_value = value,
+            TvmVar::Payment => self.payment = value,
+            TvmVar::FutureValue => self.future_value = value,
+        }
+        self.last = Some(var);
+    }
+
+    pub fn set_per_year(&mut self, value: Decimal) -> Result<(), CalcError> { // A whole number of payments, at least one
+        match value.round(0) {
+            Ok(whole) if whole == value && !whole.is_zero() && !whole.is_negative() && whole.to_f64() <= 365.0 => {
+                self.per_year = whole.to_f64() as u32;
+                Ok(())
+            },
+            _ => Err(CalcError::Domain),
+        }
+    }
+
+    pub fn clear(&mut self) { // CLR TVM, P/Y and BGN are settings and stay
+        *self = Tvm { per_year: self.per_year, begin: self.begin, ..Tvm::new() };
+    }
+
+    pub fn solve(&self, var: TvmVar, precision: u32) -> Result<Decimal, CalcError> {
+        let n: f64 = self.periods.to_f64();
+        let i: f64 = self.periodic_rate();
+        let (pv, pmt, fv): (f64, f64, f64) = (self.present_value.to_f64(), self.payment.to_f64(), self.future_value.to_f64());
+        let res: f64 = match var {
+            TvmVar::FutureValue => -(pv * growth(i, n) + pmt * self.due(i) * annuity(i, n)),
+            TvmVar::PresentValue => -(fv + pmt * self.due(i) * annuity(i, n)) / growth(i, n),
+            TvmVar::Payment => {
+                let per_payment: f64 = self.due(i) * annuity(i, n);
+                if per_payment == 0.0 {
+                    return Err(CalcError::NoSolution); // No periods to pay in
+                }
+                -(pv * growth(i, n) + fv) / per_payment
+            },
+            TvmVar::Periods => self.solve_periods(i, pv, pmt, fv)?,
+            TvmVar::Rate => self.solve_rate(n, pv, pmt, fv)? * 100.0 * self.per_year as f64,
+        };
+        if !res.is_finite() {
+            return Err(CalcError::NoSolution);
+        }
+        from_f64(res, precision)
+    }
+
+    fn periodic_rate(&self) -> f64 {
+        self.rate.to_f64() / 100.0 / self.per_year as f64
+    }
+
+    fn due(&self, i: f64) -> f64 { // Payments in advance earn a period more
+        if self.begin { 1.0 + i } else { 1.0 }
+    }
+
+    fn balance(&self, i: f64, n: f64, pv: f64, pmt: f64, fv: f64) -> f64 { // Zero when the five agree
+        pv * growth(i, n) + pmt * self.due(i) * annuity(i, n) + fv
+    }
+
+    fn solve_periods(&self, i: f64, pv: f64, pmt: f64, fv: f64) -> Result<f64, CalcError> {
+        if i.abs() < SOLVER_TOLERANCE {
+            if pmt == 0.0 {
+                return Err(CalcError::NoSolution);
+            }
+            return Ok(-(pv + fv) / pmt);
+        }
+        let paid: f64 = pmt * self.due(i);
+        let ratio: f64 = (paid - fv * i) / (paid + pv * i);
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(CalcError::NoSolution); // The payments never reach the future value
+        }
+        Ok(ratio.ln() / (1.0 + i).ln())
+    }
+
+    fn solve_rate(&self, n: f64, pv: f64, pmt: f64, fv: f64) -> Result<f64, CalcError> {
+        // Newton's method from a few starting rates, on the balance as a function of the periodic rate
+        if n <= 0.0 {
+            return Err(CalcError::NoSolution);
+        }
+        let scale: f64 = pv.abs().max(fv.abs()).max(pmt.abs() * n).max(1.0);
+        for guess in SOLVER_GUESSES {
+            let mut i: f64 = guess;
+            for _ in 0..SOLVER_STEPS {
+                let h: f64 = 1e-7 * i.abs().max(1e-3);
+                let slope: f64 = (self.balance(i + h, n, pv, pmt, fv) - self.balance(i - h, n, pv, pmt, fv)) / (2.0 * h);
+                if slope == 0.0 || !slope.is_finite() {
+                    break;
+                }
+                let step: f64 = self.balance(i, n, pv, pmt, fv) / slope;
+                i -= step;
+                if !i.is_finite() || i <= -1.0 {
+                    break; // Lost, try the next guess
+                }
+                if step.abs() < SOLVER_TOLERANCE {
+                    if self.balance(i, n, pv, pmt, fv).abs() < 1e-9 * scale {
+                        return Ok(i);
+                    }
+                    break;
+                }
+            }
+        }
+        Err(CalcError::NoSolution)
+    }
+
+    pub fn last_period(&self) -> u32 { // The viewer's last period, a part period counting as one
+        self.periods.to_f64().ceil().clamp(0.0, u32::MAX as f64) as u32
+    }
+
+    pub fn amortize(&self, period: u32, precision: u32) -> Result<AmortRow, CalcError> {
+        let i: f64 = self.periodic_rate();
+        let pmt: f64 = self.payment.to_f64();
+        let mut balance: f64 = self.present_value.to_f64();
+        let mut interest: f64 = 0.0;
+        for _ in 0..period {
+            if self.begin {
+                balance += pmt;
+                interest = balance * i;
+                balance += interest;
+            } else {
+                interest = balance * i;
+                balance += interest + pmt;
+            }
+        }
+        Ok(AmortRow {
+            period,
+            interest: from_f64(-interest, precision)?,
+            principal: from_f64(pmt + interest, precision)?,
+            balance: from_f64(balance, precision)?,
+        })
+    }
+
+    pub fn open_amort(&mut self) -> bool { // Nothing to view without a period
+        self.amort = if self.last_period() > 0 { Some((1, AmortField::Interest)) } else { None };
+        self.amort.is_some()
+    }
+
+    pub fn amort_line(&self, precision: u32) -> Option<String> { // "P3 INT -41.67" on the input line
+        let (period, field) = self.amort?;
+        let value: String = match self.amortize(period, precision) {
+            Ok(row) => {
+                let value: Decimal = row.get(field);
+                value.to_width(8).unwrap_or_else(|_| value.to_string())
+            },
+            Err(error) => error.label().to_string(),
+        };
+        Some(format!("P{} {} {}", period, field.label(), value))
+    }
+
+    pub fn label(&self) -> String { // "PMT END 12", the variable last set, the payment timing and P/Y
+        let timing: &str = if self.begin { "BGN" } else { "END" };
+        match self.last {
+            Some(var) => format!("{} {} {}", var.label(), timing, self.per_year),
+            None => format!("{} {}", timing, self.per_year),
+        }
+    }
+}
+
+impl Default for Tvm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn growth(i: f64, n: f64) -> f64 { // (1 + i)^n
+    (1.0 + i).powf(n)
+}
+
+fn annuity(i: f64, n: f64) -> f64 { // ((1 + i)^n - 1) / i, the value n payments of 1 grow to
+    if i.abs() < SOLVER_TOLERANCE {
+        n
+    } else {
+        (growth(i, n) - 1.0) / i
+    }
+}
+
+fn from_f64(value: f64, precision: u32) -> Result<Decimal, CalcError> { // Rounded to the digits f64 carries, so 999.9999999999998 is 1000
+    let whole_digits: i32 = if value == 0.0 { 1 } else { value.abs().log10().floor() as i32 + 1 };
+    let trusted: u32 = (F64_DIGITS - whole_digits).max(0) as u32;
+    Decimal::from_f64(value)?.round(precision.min(trusted))
+}
+
+impl CalcEngine {
+    pub(crate) fn handle_finance(&mut self, button: CalcButtons) -> bool { // The TVM keys, in finance mode
+        match button {
+            CalcButtons::TvmPeriods => sum_calc_operations(self, CalcOperations::Tvm(TvmVar::Periods)),
+            CalcButtons::TvmRate => sum_calc_operations(self, CalcOperations::Tvm(TvmVar::Rate)),
+            CalcButtons::TvmPresentValue => sum_calc_operations(self, CalcOperations::Tvm(TvmVar::PresentValue)),
+            CalcButtons::TvmPayment => sum_calc_operations(self, CalcOperations::Tvm(TvmVar::Payment)),
+            CalcButtons::TvmFutureValue => sum_calc_operations(self, CalcOperations::Tvm(TvmVar::FutureValue)),
+            CalcButtons::TvmPerYear => self.tvm_per_year(),
+            CalcButtons::TvmBegin => self.tvm.begin = !self.tvm.begin,
+            CalcButtons::TvmClear => self.tvm.clear(),
+            CalcButtons::TvmAmortize => {
+                if !self.tvm.open_amort() {
+                    info!("TvmAmortize: N has no periods to show");
+                }
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    pub(crate) fn tvm_key(&mut self, var: TvmVar) { // A value typed is stored, with nothing typed the variable is solved for
+        let value: Result<Decimal, CalcError> = if self.has_entry() {
+            self.entry().to_decimal(self.sum.precision)
+        } else {
+            self.tvm.solve(var, self.sum.precision)
+        };
+        match value {
+            Ok(value) => {
+                self.tvm.set(var, value);
+                self.show_result(Number::Real(value));
+            },
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn tvm_per_year(&mut self) { // P/Y, set from the value typed, or shown
+        if self.has_entry() {
+            let set: Result<(), CalcError> = self.entry().to_decimal(self.sum.precision).and_then(|value| self.tvm.set_per_year(value));
+            if let Err(error) = set {
+                self.error = Some(error);
+                return;
+            }
+        }
+        self.show_result(Number::Real(Decimal::from(self.tvm.per_year)));
+        self.index = CalcOperations::Sum;
+    }
+
+    pub(crate) fn view_amort(&mut self, button: CalcButtons) { // The keypad steps through the schedule while it is open
+        let Some((period, field)) = self.tvm.amort else {
+            return;
+        };
+        self.tvm.amort = match button {
+            CalcButtons::Add | CalcButtons::StatReviewPrev => Some((period.saturating_sub(1).max(1), field)),
+            CalcButtons::Subtract | CalcButtons::StatReviewNext => Some(((period + 1).min(self.tvm.last_period()), field)),
+            CalcButtons::Sum => Some((period, field.next())),
+            CalcButtons::Clear | CalcButtons::ClearEntry | CalcButtons::TvmAmortize => None,
+            _ => {
+                info!("{:?}: the schedule is open, + - step, = shows the next figure and AC closes", button);
+                Some((period, field))
+            },
+        };
+    }
+}
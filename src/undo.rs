@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use crate::business::Business;
+use crate::expression::Token;
+use crate::number::Number;
+use crate::programmer::IntFormat;
+use crate::stats::Stats;
+use crate::tvm::Tvm;
+use crate::{AngleMode, CalcEngine, CalcError, CalcMode, CalcOperations, EntryMode, PendingOp, SumCurrent, SumVariable, REGISTER_COUNT};
+
+pub const UNDO_DEPTH: usize = 64; // Presses that can be taken back, the oldest are forgotten past this
+
+/// The calculator state a key press can change. Unit and rate tables, and
+/// the history tape, are left out: they are not undone.
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    var: SumVariable,
+    sum: SumCurrent,
+    memory: Number,
+    index: CalcOperations,
+    last_op: Option<PendingOp>,
+    repeat: Option<(PendingOp, Number)>,
+    entry_mode: EntryMode,
+    expression: Vec<Token<Number>>,
+    paren_stack: Vec<(Number, Option<PendingOp>)>,
+    error: Option<CalcError>,
+    angle_mode: AngleMode,
+    shift: bool,
+    mode: CalcMode,
+    int_format: IntFormat,
+    show_fraction: bool,
+    polar: bool,
+    stack: [Number; 3],
+    last_x: Number,
+    stack_lift: bool,
+    stats: Stats,
+    tape: Vec<Token<Number>>,
+    registers: [Number; REGISTER_COUNT],
+    tvm: Tvm,
+    business: Business,
+}
+
+impl Snapshot {
+    pub fn take(engine: &CalcEngine) -> Self {
+        Snapshot {
+            var: engine.var.clone(),
+            sum: engine.sum,
+            memory: engine.memory,
+            index: engine.index,
+            last_op: engine.last_op,
+            repeat: engine.repeat,
+            entry_mode: engine.entry_mode,
+            expression: engine.expression.clone(),
+            paren_stack: engine.paren_stack.clone(),
+            error: engine.error,
+            angle_mode: engine.angle_mode,
+            shift: engine.shift,
+            mode: engine.mode,
+            int_format: engine.int_format,
+            show_fraction: engine.show_fraction,
+            polar: engine.polar,
+            stack: engine.stack,
+            last_x: engine.last_x,
+            stack_lift: engine.stack_lift,
+            stats: engine.stats.clone(),
+            tape: engine.tape.clone(),
+            registers: engine.registers.values,
+            tvm: engine.tvm.clone(),
+            business: engine.business.clone(),
+        }
+    }
+
+    pub fn restore(self, engine: &mut CalcEngine) {
+        engine.var = self.var;
+        engine.sum = self.sum;
+        engine.memory = self.memory;
+        engine.index = self.index;
+        engine.last_op = self.last_op;
+        engine.repeat = self.repeat;
+        engine.entry_mode = self.entry_mode;
+        engine.expression = self.expression;
+        engine.paren_stack = self.paren_stack;
+        engine.error = self.error;
+        engine.angle_mode = self.angle_mode;
+        engine.shift = self.shift;
+        engine.mode = self.mode;
+        engine.int_format = self.int_format;
+        engine.show_fraction = self.show_fraction;
+        engine.polar = self.polar;
+        engine.stack = self.stack;
+        engine.last_x = self.last_x;
+        engine.stack_lift = self.stack_lift;
+        engine.stats = self.stats;
+        engine.tape = self.tape;
+        engine.registers.values = self.registers;
+        engine.tvm = self.tvm;
+        engine.business = self.business;
+    }
+}
+
+/// Snapshots from before each press that changed something, and those undone since.
+#[derive(Clone, Default)]
+pub struct UndoStack {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        UndoStack::default()
+    }
+
+    pub fn push(&mut self, before: Snapshot) { // A new press, so what was undone can no longer be redone
+        self.undo.push_back(before);
+        if self.undo.len() > UNDO_DEPTH {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, now: Snapshot) -> Option<Snapshot> {
+        let before: Snapshot = self.undo.pop_back()?;
+        self.redo.push(now);
+        Some(before)
+    }
+
+    pub fn redo(&mut self, now: Snapshot) -> Option<Snapshot> {
+        let after: Snapshot = self.redo.pop()?;
+        self.undo.push_back(now);
+        Some(after)
+    }
+
+    pub fn depth(&self) -> (usize, usize) { // Undo and redo steps available
+        (self.undo.len(), self.redo.len())
+    }
+}
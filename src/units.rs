@@ -0,0 +1,143 @@
+use crate::csv::CsvTable;
+use crate::decimal::{Decimal, GUARD_DIGITS, MAX_PRECISION};
+use crate::{CalcEngine, CalcError};
+
+pub const UNITS_PATH: &str = "assets/units.csv";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub symbol: String,
+    factor: (Decimal, Decimal), // Numerator and denominator, so 5/9 stays exact
+    offset: Decimal,
+}
+
+impl Unit {
+    fn to_base(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> { // (value + offset) * factor
+        let scaled: Decimal = value.checked_add(self.offset)?.checked_mul(self.factor.0, MAX_PRECISION)?;
+        scaled.checked_div(self.factor.1, precision)
+    }
+
+    fn out_of_base(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+        let scaled: Decimal = value.checked_mul(self.factor.1, MAX_PRECISION)?;
+        scaled.checked_div(self.factor.0, precision)?.checked_sub(self.offset)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitCategory {
+    pub name: String,
+    pub units: Vec<Unit>, // The first is the base the others convert through
+}
+
+/// The unit definitions read from `assets/units.csv`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnitTable {
+    pub categories: Vec<UnitCategory>,
+}
+
+impl CsvTable for UnitTable {
+    const PATH: &'static str = UNITS_PATH;
+    const MISSING: &'static str = "Unit table not loaded, conversion mode has no units";
+
+    fn read_line(&mut self, fields: &[&str]) -> Result<(), String> { // category,name,symbol,factor[,offset]
+        if fields.len() < 4 || fields.len() > 5 {
+            return Err("expected category,name,symbol,factor[,offset]".to_string());
+        }
+        let factor = match fields[3].split_once('/') {
+            Some((num, den)) => (num.trim().parse::<Decimal>(), den.trim().parse::<Decimal>()),
+            None => (fields[3].parse::<Decimal>(), Ok(Decimal::ONE)),
+        };
+        let factor = match factor {
+            (Ok(num), Ok(den)) if !num.is_zero() && !den.is_zero() => (num, den),
+            _ => return Err("factor is not a non-zero number".to_string()),
+        };
+        let offset: Decimal = match fields.get(4) {
+            Some(offset) => offset.parse::<Decimal>().map_err(|_| "offset is not a number".to_string())?,
+            None => Decimal::ZERO,
+        };
+        let unit = Unit {
+            name: fields[1].to_string(),
+            symbol: fields[2].to_string(),
+            factor,
+            offset,
+        };
+        match self.categories.iter_mut().find(|category| category.name == fields[0]) {
+            Some(category) => category.units.push(unit),
+            None => self.categories.push(UnitCategory { name: fields[0].to_string(), units: vec![unit] }),
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> String {
+        format!("Units loaded: {} categories", self.categories.len())
+    }
+
+    fn install(self, engine: &mut CalcEngine) {
+        engine.converter = Converter::new(self);
+    }
+}
+
+/// The unit table and which pair of units conversion mode is set to.
+#[derive(Clone, Debug, Default)]
+pub struct Converter {
+    pub table: UnitTable,
+    pub category: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Converter {
+    pub fn new(table: UnitTable) -> Self {
+        Converter {
+            table,
+            category: 0,
+            from: 0,
+            to: 1,
+        }
+    }
+
+    fn units(&self) -> &[Unit] {
+        match self.table.categories.get(self.category) {
+            Some(category) => &category.units,
+            None => &[],
+        }
+    }
+
+    pub fn next_category(&mut self) { // Starts on the first two units of the next category
+        if !self.table.categories.is_empty() {
+            self.category = (self.category + 1) % self.table.categories.len();
+        }
+        self.from = 0;
+        self.to = 1;
+    }
+
+    pub fn next_from(&mut self) {
+        self.from = (self.from + 1) % self.units().len().max(1);
+    }
+
+    pub fn next_to(&mut self) {
+        self.to = (self.to + 1) % self.units().len().max(1);
+    }
+
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.from, &mut self.to);
+    }
+
+    pub fn convert(&self, value: Decimal, precision: u32) -> Result<Decimal, CalcError> {
+        match (self.units().get(self.from), self.units().get(self.to)) {
+            (Some(from), Some(to)) => {
+                let working: u32 = (precision + GUARD_DIGITS).min(MAX_PRECISION);
+                to.out_of_base(from.to_base(value, working)?, working)?.round(precision)
+            },
+            _ => Err(CalcError::InvalidInput), // No table loaded, or too few units in it
+        }
+    }
+
+    pub fn label(&self) -> String { // Shown on the device screen, such as "m>ft"
+        match (self.units().get(self.from), self.units().get(self.to)) {
+            (Some(from), Some(to)) => format!("{}>{}", from.symbol, to.symbol),
+            _ => "NO UNITS".to_string(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use calc_sim::{CalcError, PendingOp};
+use calc_sim::business::{Business, MarginVar};
+use calc_sim::decimal::Decimal;
+
+fn dec(text: &str) -> Decimal {
+    text.parse::<Decimal>().unwrap()
+}
+
+#[cfg(test)]
+mod business_test_tax {
+    use super::*;
+
+    #[test]
+    fn check_tax_add_and_remove() {
+        let mut business = Business::new();
+        business.set_tax_rate(dec("8")).unwrap();
+        assert_eq!(business.tax_add(dec("50"), 12), Ok(dec("54")));
+        assert_eq!(business.tax_remove(dec("54"), 12), Ok(dec("50")));
+    }
+
+    #[test]
+    fn check_negative_rate() {
+        let mut business = Business::new();
+        assert_eq!(business.set_tax_rate(dec("-5")), Err(CalcError::Domain));
+        assert_eq!(business.tax_rate, Decimal::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod business_test_margin {
+    use super::*;
+
+    #[test]
+    fn check_solve_each() {
+        let mut business = Business::new();
+        business.set(MarginVar::Cost, dec("75"));
+        business.set(MarginVar::Margin, dec("25"));
+        assert_eq!(business.solve(MarginVar::Sell, 12), Ok(dec("100")));
+        business.set(MarginVar::Sell, dec("100"));
+        assert_eq!(business.solve(MarginVar::Cost, 12), Ok(dec("75")));
+        assert_eq!(business.solve(MarginVar::Margin, 12), Ok(dec("25")));
+        assert_eq!(business.label(), "SEL TAX 0%");
+    }
+
+    #[test]
+    fn check_impossible() {
+        let mut business = Business::new();
+        business.set(MarginVar::Margin, dec("100")); // Nothing left for the cost
+        assert_eq!(business.solve(MarginVar::Sell, 12), Err(CalcError::DivideByZero));
+        assert_eq!(business.solve(MarginVar::Margin, 12), Err(CalcError::DivideByZero)); // No selling price
+    }
+
+    #[test]
+    fn check_operators() {
+        assert_eq!(PendingOp::Markup.apply(dec("100"), dec("20"), 12), Ok(dec("125")));
+        assert_eq!(PendingOp::PercentChange.apply(dec("80"), dec("100"), 12), Ok(dec("25")));
+        assert_eq!(PendingOp::PercentChange.apply(dec("0"), dec("100"), 12), Err(CalcError::DivideByZero));
+    }
+}
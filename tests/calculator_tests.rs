@@ -0,0 +1,61 @@
+use bevy::prelude::KeyCode;
+use calc_sim::OpIndex;
+use calc_sim::calculator::CalcButtons;
+
+#[cfg(test)]
+mod calculator_test_keypad {
+    use super::*;
+
+    #[test]
+    fn check_index_round_trip() {
+        let mut op_index = OpIndex::new();
+        op_index.add_entity();
+        for button in [CalcButtons::Sum, CalcButtons::Num7, CalcButtons::NoneButtonLightPanel] {
+            let index: u32 = button.to_index(&op_index).unwrap();
+            assert_eq!(CalcButtons::from_index(&op_index, index), Some(button));
+        }
+        assert_eq!(CalcButtons::from_index(&op_index, 0), None);
+        assert_eq!(CalcButtons::Sin.to_index(&op_index), None); // Only on the 2nd layer, no mesh of its own
+    }
+
+    #[test]
+    fn check_shifted_keys() {
+        assert_eq!(CalcButtons::from_shift_key(&KeyCode::Equal), Some(CalcButtons::Add));
+        assert_eq!(CalcButtons::from_shift_key(&KeyCode::Digit8), Some(CalcButtons::Multiply));
+        assert_eq!(CalcButtons::from_shift_key(&KeyCode::Digit5), Some(CalcButtons::Percent));
+        assert_eq!(CalcButtons::from_shift_key(&KeyCode::Digit7), None); // Falls back to from_key
+        assert_eq!(CalcButtons::from_key(&KeyCode::Equal), Some(CalcButtons::Sum));
+    }
+}
+
+#[cfg(test)]
+mod calculator_test_names {
+    use super::*;
+
+    fn variants() -> Vec<String> { // Read from the enum itself, so a new key cannot be missed
+        let source: String = std::fs::read_to_string("src/calculator.rs").unwrap();
+        let body: &str = source.split("pub enum CalcButtons {").nth(1).unwrap().split("\n}").next().unwrap();
+        body.lines()
+            .map(|line| line.split("//").next().unwrap().trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.trim_end_matches(',').split('(').next().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn check_every_key_named() {
+        let variants: Vec<String> = variants();
+        assert!(variants.len() > 100);
+        for name in variants.iter().filter(|name| name.as_str() != "Register") {
+            let button: Option<CalcButtons> = CalcButtons::from_name(name);
+            assert_eq!(button.map(|button| format!("{:?}", button)).as_deref(), Some(name.as_str()), "{} is missing from NAMED", name);
+        }
+    }
+
+    #[test]
+    fn check_register_named() {
+        assert_eq!(CalcButtons::from_name("Register(25)"), Some(CalcButtons::Register(25)));
+        assert_eq!(CalcButtons::from_name("Register(A)"), None);
+        assert_eq!(CalcButtons::from_name("Nope"), None);
+    }
+}
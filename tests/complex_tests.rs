@@ -0,0 +1,49 @@
+use calc_sim::{AngleMode, CalcError, PendingOp};
+use calc_sim::complex::Complex;
+use calc_sim::decimal::Decimal;
+
+#[cfg(test)]
+mod complex_test_arithmatic {
+    use super::*;
+
+    fn z(re: &str, im: &str) -> Complex {
+        Complex::new(re.parse::<Decimal>().unwrap(), im.parse::<Decimal>().unwrap())
+    }
+
+    #[test]
+    fn check_operators() {
+        assert_eq!(z("3", "4").checked_add(z("1", "-2")), Ok(z("4", "2")));
+        assert_eq!(z("3", "4").checked_sub(z("1", "-2")), Ok(z("2", "6")));
+        assert_eq!(z("1", "2").checked_mul(z("3", "4"), 12), Ok(z("-5", "10")));
+        assert_eq!(z("-5", "10").checked_div(z("3", "4"), 12), Ok(z("1", "2")));
+        assert_eq!(z("1", "1").checked_div(z("0", "0"), 12), Err(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_powers() {
+        assert_eq!(z("0", "1").checked_powi(2, 12), Ok(z("-1", "0")));
+        assert_eq!(z("1", "1").checked_powi(4, 12), Ok(z("-4", "0")));
+        assert_eq!(z("0", "1").checked_powi(-1, 12), Ok(z("0", "-1")));
+        assert_eq!(z("0", "1").apply(PendingOp::Power, z("0.5", "0"), 12), None);
+        assert_eq!(z("0", "1").apply(PendingOp::And, z("1", "0"), 12), None);
+    }
+
+    #[test]
+    fn check_polar() {
+        assert_eq!(z("3", "4").magnitude(12), Ok(Decimal::from(5)));
+        assert_eq!(z("0", "2").argument(12, AngleMode::Degrees), Ok(Decimal::from(90)));
+        assert_eq!(z("-1", "0").argument(12, AngleMode::Degrees), Ok(Decimal::from(180)));
+        assert_eq!(z("1", "1").to_polar(4, AngleMode::Radians), Ok(("1.4142".parse().unwrap(), "0.7854".parse().unwrap())));
+        assert_eq!(z("-4", "0").sqrt(12), Ok(z("0", "2")));
+        assert_eq!(z("3", "4").conj(), Ok(z("3", "-4")));
+    }
+
+    #[test]
+    fn check_display() {
+        assert_eq!(z("3", "4").to_string(), "3+4i");
+        assert_eq!(z("3", "-4").to_string(), "3-4i");
+        assert_eq!(z("0", "-1").to_string(), "-i");
+        assert_eq!(z("0", "2.5").to_string(), "2.5i");
+        assert_eq!(z("7", "0").to_string(), "7");
+    }
+}
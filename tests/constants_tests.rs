@@ -0,0 +1,47 @@
+use calc_sim::constants::{ConstantPicker, ConstantTable, CONSTANTS_PATH};
+use calc_sim::csv::CsvTable;
+
+#[cfg(test)]
+mod constants_test_table {
+    use super::*;
+
+    #[test]
+    fn check_asset_loads() {
+        let table = ConstantTable::load(CONSTANTS_PATH).unwrap();
+        let symbols: Vec<&str> = table.constants.iter().map(|constant| constant.symbol.as_str()).collect();
+        assert_eq!(symbols[..6], ["π", "e", "φ", "√2", "c", "g"]);
+        assert_eq!(table.constants[0].value.to_string(), "3.1415926535897932384626433833");
+        assert_eq!(table.constants[4].unit, "m/s");
+    }
+
+    #[test]
+    fn check_exponents() {
+        let table = ConstantTable::parse("big,B,6.02214076e23\nsmall,s,4.1356676969239E-15,eV·s\n").unwrap();
+        assert_eq!(table.constants[0].value.to_string(), "602214076000000000000000");
+        assert_eq!(table.constants[1].value.to_string(), "0.0000000000000041356676969239");
+    }
+
+    #[test]
+    fn check_bad_lines() {
+        assert_eq!(ConstantTable::parse("pi,π\n"), Err("line 1: expected name,symbol,value[,unit]".to_string()));
+        assert_eq!(ConstantTable::parse("pi,π,three\n"), Err("line 1: value is not a number".to_string()));
+        assert!(ConstantTable::parse("huge,H,1e40\n").is_err()); // Past what a decimal holds
+        assert_eq!(
+            ConstantTable::parse("Planck constant,h,6.62607015e-34,J·s\n"),
+            Err("line 1: value needs more than 28 decimal places".to_string()),
+        );
+    }
+
+    #[test]
+    fn check_picker_steps() {
+        let mut picker = ConstantPicker::new(ConstantTable::parse("one,a,1\ntwo,b,2\nthree,c,3\n").unwrap());
+        assert!(picker.open());
+        picker.prev();
+        assert_eq!(picker.current().unwrap().name, "three"); // Wraps to the end
+        picker.next();
+        assert_eq!(picker.line(), Some("1/3 a 1".to_string()));
+        picker.jump(8);
+        assert_eq!(picker.selected, 2);
+        assert!(!ConstantPicker::default().open()); // Nothing to open
+    }
+}
@@ -0,0 +1,67 @@
+use calc_sim::CalcEngine;
+use calc_sim::csv::CsvTable;
+
+#[derive(Debug, Default, PartialEq)]
+struct Lines {
+    lines: Vec<Vec<String>>,
+}
+
+impl CsvTable for Lines {
+    const PATH: &'static str = "assets/missing.txt";
+    const MISSING: &'static str = "Lines not loaded";
+
+    fn read_line(&mut self, fields: &[&str]) -> Result<(), String> {
+        if fields.len() != 2 {
+            return Err("expected two fields".to_string());
+        }
+        self.lines.push(fields.iter().map(|field| field.to_string()).collect());
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self, String> {
+        if self.lines.is_empty() {
+            return Err("no lines".to_string());
+        }
+        Ok(self)
+    }
+
+    fn summary(&self) -> String {
+        format!("Lines loaded: {}", self.lines.len())
+    }
+
+    fn install(self, _engine: &mut CalcEngine) {}
+}
+
+#[cfg(test)]
+mod csv_test_table {
+    use super::*;
+
+    #[test]
+    fn check_comments_and_blank_lines() {
+        let table = Lines::parse("# comment\n\n  # indented comment\na,b\n\nc,d\n").unwrap();
+        assert_eq!(table.lines.len(), 2);
+    }
+
+    #[test]
+    fn check_fields_trimmed() {
+        let table = Lines::parse("  a , b  \r\n").unwrap();
+        assert_eq!(table.lines[0], vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn check_line_numbers() {
+        assert_eq!(Lines::parse("a,b,c"), Err("line 1: expected two fields".to_string()));
+        assert_eq!(Lines::parse("# comment\n\na,b\nc\n"), Err("line 4: expected two fields".to_string())); // Skipped lines still count
+    }
+
+    #[test]
+    fn check_finish() {
+        assert_eq!(Lines::parse("# only a comment\n"), Err("no lines".to_string()));
+    }
+
+    #[test]
+    fn check_missing_file() {
+        let err: String = Lines::load(Lines::PATH).unwrap_err();
+        assert!(err.starts_with("assets/missing.txt: "));
+    }
+}
@@ -0,0 +1,61 @@
+use calc_sim::CalcError;
+use calc_sim::csv::CsvTable;
+use calc_sim::currency::{CurrencyConverter, RateTable, RATES_PATH};
+use calc_sim::decimal::Decimal;
+
+#[cfg(test)]
+mod currency_test_rates {
+    use super::*;
+
+    const RATES: &str = "base,USD\nEUR,0.8,2026-10-16 16:00 UTC\nGBP,0.5,2026-10-15 09:30 UTC\n";
+
+    fn convert(from: &str, to: &str, value: &str) -> Result<String, CalcError> {
+        let mut converter = CurrencyConverter::new(RateTable::parse(RATES).unwrap());
+        converter.from = from.to_string();
+        converter.to = to.to_string();
+        converter.convert(value.parse::<Decimal>().unwrap(), 12).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn check_asset_loads() {
+        let table = RateTable::load(RATES_PATH).unwrap();
+        assert_eq!(table.base, "USD");
+        assert_eq!(table.codes()[..3], ["USD", "EUR", "GBP"]);
+        assert!(table.updated("EUR").is_some());
+    }
+
+    #[test]
+    fn check_base_and_cross_rates() {
+        assert_eq!(convert("USD", "EUR", "10"), Ok("8".to_string()));
+        assert_eq!(convert("EUR", "USD", "8"), Ok("10".to_string()));
+        assert_eq!(convert("EUR", "GBP", "8"), Ok("5".to_string()));
+        assert_eq!(convert("GBP", "EUR", "1"), Ok("1.6".to_string()));
+        assert_eq!(convert("EUR", "EUR", "3"), Ok("3".to_string()));
+    }
+
+    #[test]
+    fn check_missing_currency() {
+        assert_eq!(convert("USD", "XYZ", "1"), Err(CalcError::NoRate));
+        assert_eq!(convert("XYZ", "USD", "1"), Err(CalcError::NoRate));
+        assert_eq!(CurrencyConverter::default().convert(Decimal::ONE, 12), Err(CalcError::NoRate));
+    }
+
+    #[test]
+    fn check_cycling_codes() {
+        let mut converter = CurrencyConverter::new(RateTable::parse(RATES).unwrap());
+        assert_eq!(converter.label(), "USD>EUR");
+        converter.next_to();
+        converter.next_from();
+        assert_eq!(converter.label(), "EUR>GBP");
+        converter.next_to();
+        converter.swap();
+        assert_eq!(converter.label(), "USD>EUR");
+    }
+
+    #[test]
+    fn check_bad_files() {
+        assert!(RateTable::parse("EUR,0.8,today").is_err());
+        assert!(RateTable::parse("base,USD\nEUR,-0.8,today").is_err());
+        assert!(RateTable::parse("base,USD\nEUR,0.8").is_err());
+    }
+}
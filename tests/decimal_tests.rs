@@ -0,0 +1,96 @@
+use calc_sim::CalcError;
+use calc_sim::decimal::Decimal;
+
+#[cfg(test)]
+mod decimal_test_arithmatic {
+    use super::*;
+
+    fn dec(input: &str) -> Decimal {
+        input.parse::<Decimal>().unwrap()
+    }
+
+    #[test]
+    fn check_parse_and_display() {
+        assert_eq!(dec("12.50").to_string(), "12.5");
+        assert_eq!(dec("-0.05").to_string(), "-0.05");
+        assert_eq!(dec("007").to_string(), "7");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn check_add_is_exact() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Ok(dec("0.3")));
+        assert_eq!(dec("19.99").checked_sub(dec("20")), Ok(dec("-0.01")));
+    }
+
+    #[test]
+    fn check_multiply() {
+        assert_eq!(dec("1.1").checked_mul(dec("1.1"), 12), Ok(dec("1.21")));
+        assert_eq!(dec("0.125").checked_mul(dec("0.5"), 2), Ok(dec("0.06")));
+    }
+
+    #[test]
+    fn check_divide_precision() {
+        assert_eq!(Decimal::from(1).checked_div(Decimal::from(3), 4), Ok(dec("0.3333")));
+        assert_eq!(Decimal::from(2).checked_div(Decimal::from(3), 4), Ok(dec("0.6667")));
+        assert_eq!(Decimal::from(10).checked_div(dec("0.25"), 12), Ok(Decimal::from(40)));
+        assert_eq!(Decimal::from(1).checked_div(Decimal::ZERO, 12), Err(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_overflow() {
+        let big = dec("99999999999999999999");
+        assert_eq!(big.checked_mul(big, 12), Err(CalcError::Overflow));
+        assert_eq!(big.checked_add(Decimal::ONE), Ok(dec("100000000000000000000")));
+    }
+
+    #[test]
+    fn check_min_mantissa_overflows() {
+        let min = Decimal::new(i128::MIN, 0);
+        assert_eq!(min.checked_neg(), Err(CalcError::Overflow));
+        assert_eq!(min.checked_abs(), Err(CalcError::Overflow));
+        assert_eq!(min.checked_rem(Decimal::new(-1, 0)), Err(CalcError::Overflow));
+        assert_eq!(Decimal::ZERO.checked_sub(min), Err(CalcError::Overflow));
+        assert_eq!(dec("-2.5").checked_abs(), Ok(dec("2.5")));
+    }
+
+    #[test]
+    fn check_round_far_past_scale() {
+        let tiny = Decimal::new(i128::MAX, 60);
+        assert_eq!(tiny.round(12), Ok(Decimal::ZERO));
+        assert_eq!(Decimal::new(-5, 45).round(0), Ok(Decimal::ZERO));
+    }
+
+    #[test]
+    fn check_divide_by_min_mantissa() {
+        let min = Decimal::new(i128::MIN, 0);
+        assert_eq!(Decimal::new(i128::MAX, 0).checked_div(min, 0), Ok(Decimal::from(-1)));
+        assert_eq!(Decimal::new(1, 0).checked_div(min, 0), Ok(Decimal::ZERO));
+    }
+
+    #[test]
+    fn check_power() {
+        assert_eq!(dec("1.1").checked_pow(Decimal::from(2), 12), Ok(dec("1.21")));
+        assert_eq!(Decimal::from(2).checked_pow(Decimal::from(-3), 12), Ok(dec("0.125")));
+        assert_eq!(Decimal::from(-2).checked_pow(Decimal::from(3), 12), Ok(Decimal::from(-8)));
+        assert_eq!(Decimal::from(4).checked_pow(dec("0.5"), 12), Ok(Decimal::from(2)));
+        assert_eq!(Decimal::from(-4).checked_pow(dec("0.5"), 12), Err(CalcError::Domain));
+        assert_eq!(Decimal::ZERO.checked_pow(Decimal::from(-1), 12), Err(CalcError::DivideByZero));
+        assert_eq!(Decimal::from(10).checked_pow(Decimal::from(40), 12), Err(CalcError::Overflow));
+    }
+
+    #[test]
+    fn check_to_width() {
+        assert_eq!(dec("3.14159265").to_width(8), Ok("3.141593".to_string()));
+        assert_eq!(dec("-12.5").to_width(8), Ok("-12.5".to_string()));
+        assert_eq!(dec("123456789").to_width(8), Ok("1.2346e8".to_string()));
+        assert_eq!(dec("299792458").to_width(8), Ok("2.9979e8".to_string()));
+        assert_eq!(dec("602214076000000000000000").to_width(8), Ok("6.022e23".to_string()));
+        assert_eq!(dec("0.0000000000000000001602176634").to_width(8), Ok("1.6e-19".to_string()));
+        assert_eq!(dec("0.0000123").to_width(8), Ok("1.23e-5".to_string())); // "0.000012" would keep two digits
+        assert_eq!(dec("0.001").to_width(8), Ok("0.001".to_string()));
+        assert_eq!(dec("99999").to_width(4), Ok("1e5".to_string())); // Rounding carries into the exponent
+        assert_eq!(dec("-123456").to_width(3), Err(CalcError::Overflow));
+        assert_eq!(Decimal::from_f64(f64::NAN), Err(CalcError::Domain));
+    }
+}
@@ -0,0 +1,66 @@
+use calc_sim::decimal::Decimal;
+use calc_sim::CalcError;
+use calc_sim::expression::evaluate;
+
+#[cfg(test)]
+mod expression_test_evaluate {
+    use super::*;
+
+    fn dec(input: &str) -> Decimal {
+        input.parse::<Decimal>().unwrap()
+    }
+
+    #[test]
+    fn check_precedence() {
+        assert_eq!(evaluate("2+3*4"), Ok(dec("14")));
+        assert_eq!(evaluate("2*3+4"), Ok(dec("10")));
+        assert_eq!(evaluate("10-4/2"), Ok(dec("8")));
+        assert_eq!(evaluate("2*3^2"), Ok(dec("18")));
+    }
+
+    #[test]
+    fn check_power_right_associative() {
+        assert_eq!(evaluate("2^3^2"), Ok(dec("512")));
+    }
+
+    #[test]
+    fn check_minus_below_power() {
+        assert_eq!(evaluate("-2^2"), Ok(dec("-4")));
+        assert_eq!(evaluate("2^-1"), Ok(dec("0.5")));
+        assert_eq!(evaluate("-2*3"), Ok(dec("-6")));
+        assert_eq!(evaluate("--2^2"), Ok(dec("4")));
+    }
+
+    #[test]
+    fn check_nesting_limit() {
+        let nested: String = "(".repeat(20) + "1" + &")".repeat(20);
+        assert_eq!(evaluate(&nested), Ok(dec("1")));
+        let deep: String = "(".repeat(100_000) + "1" + &")".repeat(100_000);
+        assert_eq!(evaluate(&deep), Err(CalcError::InvalidInput));
+        let signs: String = "-".repeat(100_000) + "1";
+        assert_eq!(evaluate(&signs), Err(CalcError::InvalidInput));
+        let powers: String = "2^".repeat(100_000) + "1";
+        assert_eq!(evaluate(&powers), Err(CalcError::InvalidInput));
+    }
+
+    #[test]
+    fn check_left_associative() {
+        assert_eq!(evaluate("10-4-3"), Ok(dec("3")));
+        assert_eq!(evaluate("64/4/2"), Ok(dec("8")));
+    }
+
+    #[test]
+    fn check_parentheses() {
+        assert_eq!(evaluate("(2+3)*4/7"), Ok(dec("2.857142857143")));
+        assert_eq!(evaluate("-(1.5 + 0.5) * 3"), Ok(dec("-6")));
+    }
+
+    #[test]
+    fn check_errors() {
+        assert_eq!(evaluate(""), Err(CalcError::InvalidInput));
+        assert_eq!(evaluate("(2+3"), Err(CalcError::InvalidInput));
+        assert_eq!(evaluate("2+3)"), Err(CalcError::InvalidInput));
+        assert_eq!(evaluate("2+"), Err(CalcError::InvalidInput));
+        assert_eq!(evaluate("1/0"), Err(CalcError::DivideByZero));
+    }
+}
@@ -0,0 +1,56 @@
+use calc_sim::{CalcError, PendingOp};
+use calc_sim::decimal::Decimal;
+use calc_sim::fraction::Fraction;
+
+#[cfg(test)]
+mod fraction_test_arithmatic {
+    use super::*;
+
+    fn frac(num: i128, den: i128) -> Fraction {
+        Fraction::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn check_reduced() {
+        assert_eq!(frac(6, 8), frac(3, 4));
+        assert_eq!(frac(3, -6).to_string(), "-1/2");
+        assert_eq!(frac(10, 5).to_string(), "2");
+        assert_eq!(Fraction::new(1, 0), Err(CalcError::DivideByZero));
+        assert_eq!(Fraction::from_decimal("0.125".parse::<Decimal>().unwrap()), Ok(frac(1, 8)));
+    }
+
+    #[test]
+    fn check_operators() {
+        assert_eq!(frac(1, 2).checked_add(frac(1, 3)), Ok(frac(5, 6)));
+        assert_eq!(frac(1, 2).checked_sub(frac(3, 4)), Ok(frac(-1, 4)));
+        assert_eq!(frac(2, 3).checked_mul(frac(9, 4)), Ok(frac(3, 2)));
+        assert_eq!(frac(2, 3).checked_div(frac(4, 9)), Ok(frac(3, 2)));
+        assert_eq!(frac(7, 2).checked_rem(frac(1, 1)), Ok(frac(1, 2)));
+        assert_eq!(frac(2, 3).checked_powi(-2), Ok(frac(9, 4)));
+        assert_eq!(frac(1, 3).apply(PendingOp::Power, frac(1, 2)), None);
+        assert_eq!(frac(1, 3).checked_div(frac(0, 1)), Err(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_min_numerator_overflows() {
+        let min = frac(i128::MIN, 1);
+        assert_eq!(min.checked_neg(), Err(CalcError::Overflow));
+        assert_eq!(frac(0, 1).checked_sub(min), Err(CalcError::Overflow));
+        assert_eq!(frac(-1, 2).checked_neg(), Ok(frac(1, 2)));
+    }
+
+    #[test]
+    fn check_mixed() {
+        assert_eq!(frac(5, 3).to_mixed(), "1 2/3");
+        assert_eq!(frac(-7, 2).to_mixed(), "-3 1/2");
+        assert_eq!(frac(2, 3).to_mixed(), "2/3");
+        assert_eq!(frac(4, 1).to_mixed(), "4");
+    }
+
+    #[test]
+    fn check_to_decimal() {
+        assert_eq!(frac(1, 3).to_decimal(4), Ok("0.3333".parse::<Decimal>().unwrap()));
+        assert_eq!(frac(9, 16).sqrt(), Some(frac(3, 4)));
+        assert_eq!(frac(2, 1).sqrt(), None);
+    }
+}
@@ -0,0 +1,64 @@
+use calc_sim::PendingOp;
+use calc_sim::decimal::Decimal;
+use calc_sim::expression::Token;
+use calc_sim::history::{CalcHistory, Calculation, HISTORY_LIMIT};
+use calc_sim::number::Number;
+
+#[cfg(test)]
+mod history_test_record {
+    use super::*;
+
+    fn calculation(lhs: i32, rhs: i32) -> Calculation {
+        let tokens = vec![
+            Token::Number(Number::Real(Decimal::from(lhs))),
+            Token::Op(PendingOp::Add),
+            Token::Number(Number::Real(Decimal::from(rhs))),
+        ];
+        Calculation::new(tokens, Number::Real(Decimal::from(lhs + rhs)), format!("{} + {} = {}", lhs, rhs, lhs + rhs))
+    }
+
+    #[test]
+    fn check_parts() {
+        let calculation = calculation(2, 3);
+        assert_eq!(calculation.operands(), [Number::Real(Decimal::from(2)), Number::Real(Decimal::from(3))]);
+        assert_eq!(calculation.operators(), [PendingOp::Add]);
+        assert!(calculation.timestamp > 0);
+    }
+
+    #[test]
+    fn check_time_label() {
+        let mut calculation = calculation(2, 3);
+        calculation.timestamp = 86_400 * 365 + 14 * 3600 + 5 * 60 + 9;
+        assert_eq!(calculation.time_label(), "14:05:09 UTC");
+    }
+
+    #[test]
+    fn check_limit() {
+        let mut history = CalcHistory::new();
+        for i in 0..HISTORY_LIMIT as i32 + 5 {
+            history.record(calculation(i, 1));
+        }
+        assert_eq!(history.entries.len(), HISTORY_LIMIT);
+        assert_eq!(history.entries[0].tape, "5 + 1 = 6"); // The oldest dropped off
+    }
+
+    #[test]
+    fn check_scroll() {
+        let mut history = CalcHistory::new();
+        for i in 0..5 {
+            history.record(calculation(i, 1));
+        }
+        assert_eq!(history.visible(0), Some(4)); // Newest at the top
+        assert_eq!(history.visible(4), Some(0));
+        assert_eq!(history.visible(5), None);
+        history.scroll_back(3);
+        history.scroll_back(3);
+        history.scroll_back(3);
+        assert_eq!(history.scroll, 2); // No further than a full panel
+        assert_eq!(history.visible(0), Some(2));
+        history.scroll_forward();
+        assert_eq!(history.scroll, 1);
+        history.record(calculation(9, 1));
+        assert_eq!(history.scroll, 0);
+    }
+}
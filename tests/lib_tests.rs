@@ -1,4 +1,14 @@
-use calc_sim::SumCurrent;
+use calc_sim::{AngleMode, CalcEngine, CalcMode, CalcError, DisplayState, EntryMode, PendingOp, SumCurrent, UnaryOp};
+use calc_sim::calculator::CalcButtons;
+use calc_sim::number::Number;
+
+fn press_all(engine: &mut CalcEngine, buttons: &[CalcButtons]) -> DisplayState {
+    let mut display = engine.display();
+    for button in buttons {
+        display = engine.press(button.clone());
+    }
+    display
+}
 
 #[cfg(test)]
 mod lib_test_arithmatic {
@@ -6,64 +16,2190 @@ mod lib_test_arithmatic {
 
     #[test]
     fn check_sum() {
-        let mut sum = SumCurrent::new_from(5.0);
+        let sum = SumCurrent::new_from(5.0).unwrap();
         assert_eq!(sum.sum, 5.0);
     }
 
     #[test]
     fn check_add() {
-        let mut sum = SumCurrent::new_from(5.0);
+        let mut sum = SumCurrent::new_from(5.0).unwrap();
         assert_eq!(sum.sum, 5.0);
 
-        let sum1 = sum.add(2);
+        let sum1 = sum.add(2).unwrap();
         assert_eq!(sum1.sum, 7.0);
 
-        let sum2 = sum.add(12.0);
+        let sum2 = sum.add(12.0).unwrap();
         assert_eq!(sum2.sum, 17.0);
 
-        let sum3 = sum.add(24.0 as usize);
+        let sum3 = sum.add(24.0 as usize).unwrap();
         assert_eq!(sum3.sum, 29.0);
     }
 
     #[test]
     fn check_subtract() {
-        let mut sum = SumCurrent::new_from(120.0);
+        let mut sum = SumCurrent::new_from(120.0).unwrap();
 
-        let sum1 = sum.subtract(2);
+        let sum1 = sum.subtract(2).unwrap();
         assert_eq!(sum1.sum, 118.0);
 
-        let sum2 = sum.subtract(12.0);
+        let sum2 = sum.subtract(12.0).unwrap();
         assert_eq!(sum2.sum, 108.0);
 
-        let sum3 = sum.subtract(24 as usize);
+        let sum3 = sum.subtract(24_usize).unwrap();
         assert_eq!(sum3.sum, 96.0);
     }
 
     #[test]
     fn check_multiply() {
-        let mut sum = SumCurrent::new_from(120.0);
+        let mut sum = SumCurrent::new_from(120.0).unwrap();
         
-        let sum1 = sum.multiply(2);
+        let sum1 = sum.multiply(2).unwrap();
         assert_eq!(sum1.sum, 240.0);
         
-        let sum2 = sum.multiply(12.0);
+        let sum2 = sum.multiply(12.0).unwrap();
         assert_eq!(sum2.sum, 1440.0);
         
-        let sum3 = sum.multiply(24 as usize);
+        let sum3 = sum.multiply(24_usize).unwrap();
         assert_eq!(sum3.sum, 2880.0);
     }
 
     #[test]
     fn check_divide() {
-        let mut sum = SumCurrent::new_from(120.0);
+        let mut sum = SumCurrent::new_from(120.0).unwrap();
         
-        let sum1 = sum.divide(2);
+        let sum1 = sum.divide(2).unwrap();
         assert_eq!(sum1.sum, 60.0);
         
-        let sum2 = sum.divide(12.0);
+        let sum2 = sum.divide(12.0).unwrap();
         assert_eq!(sum2.sum, 10.0);
         
-        let sum3 = sum.divide(24 as usize);
+        let sum3 = sum.divide(24_usize).unwrap();
         assert_eq!(sum3.sum, 5.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn check_divide_by_zero() {
+        let mut sum = SumCurrent::new_from(120.0).unwrap();
+        assert_eq!(sum.divide(0).map(|res| res.sum), Err(CalcError::DivideByZero));
+        assert_eq!(SumCurrent::new_from(f64::INFINITY).map(|res| res.sum), Err(CalcError::Overflow));
+    }
+}
+
+#[cfg(test)]
+mod lib_test_engine {
+    use super::*;
+
+    fn press_all(engine: &mut CalcEngine, buttons: &[CalcButtons]) -> String {
+        let mut sum = String::new();
+        for button in buttons {
+            sum = engine.press(button.clone()).sum;
+        }
+        sum
+    }
+
+    #[test]
+    fn check_digit_entry() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Decimal, CalcButtons::Num5]);
+        assert_eq!(engine.display().input, "12.5");
+    }
+
+    #[test]
+    fn check_chain_sequence() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add,
+            CalcButtons::Num3, CalcButtons::Add,
+            CalcButtons::Num4, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "9");
+    }
+
+    #[test]
+    fn check_zero_result_keeps_chaining() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Subtract,
+            CalcButtons::Num5, CalcButtons::Subtract,
+            CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "-3");
+    }
+
+    #[test]
+    fn check_clear() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num8, CalcButtons::Multiply,
+            CalcButtons::Num2, CalcButtons::Sum,
+            CalcButtons::Clear,
+        ]);
+        assert_eq!(sum, "0");
+        assert_eq!(engine.display().input, "0");
+        assert_eq!(engine.last_op, None);
+    }
+    #[test]
+    fn check_decimal_sum_is_exact() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Decimal, CalcButtons::Num1, CalcButtons::Add,
+            CalcButtons::Decimal, CalcButtons::Num2, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "0.3");
+    }
+
+    #[test]
+    fn check_configurable_precision() {
+        let mut engine = CalcEngine::with_precision(3);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Divide,
+            CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "0.667");
+    }
+    #[test]
+    fn check_entry_modes() {
+        let sequence = [
+            CalcButtons::Num2, CalcButtons::Add,
+            CalcButtons::Num3, CalcButtons::Multiply,
+            CalcButtons::Num4, CalcButtons::Sum,
+        ];
+        let mut engine = CalcEngine::new();
+        assert_eq!(press_all(&mut engine, &sequence), "20");
+
+        engine.press(CalcButtons::EntryMode);
+        assert_eq!(engine.entry_mode, EntryMode::Algebraic);
+        assert_eq!(press_all(&mut engine, &sequence), "14");
+    }
+
+    #[test]
+    fn check_algebraic_continues_from_result() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num6, CalcButtons::Sum,
+            CalcButtons::Subtract, CalcButtons::Num2,
+            CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "2");
+    }
+    #[test]
+    fn check_chain_parentheses() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add,
+            CalcButtons::OpenParen, CalcButtons::Num3, CalcButtons::Multiply, CalcButtons::Num4, CalcButtons::CloseParen,
+            CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "28");
+    }
+
+    #[test]
+    fn check_sum_auto_closes_parentheses() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Subtract,
+            CalcButtons::OpenParen, CalcButtons::Num2, CalcButtons::Add,
+            CalcButtons::OpenParen, CalcButtons::Num1, CalcButtons::Add, CalcButtons::Num1,
+        ]);
+        assert_eq!(engine.display().depth, 2);
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "6");
+        assert_eq!(engine.display().depth, 0);
+
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Multiply,
+            CalcButtons::OpenParen, CalcButtons::Num3, CalcButtons::Add, CalcButtons::Num4,
+            CalcButtons::Sum,
+        ]);
+        assert_eq!(sum, "14");
+    }
+
+    #[test]
+    fn check_unbalanced_close_is_an_error() {
+        let mut engine = CalcEngine::new();
+        let display = super::press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::CloseParen]);
+        assert_eq!(display.error, Some(CalcError::InvalidInput));
+    }
+}
+
+#[cfg(test)]
+mod lib_test_memory {
+    use super::*;
+
+    #[test]
+    fn check_memory_add_and_recall() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num1, CalcButtons::Decimal, CalcButtons::Num5, CalcButtons::MemoryAdd] {
+            engine.press(button);
+        }
+        assert!(engine.display().memory);
+
+        engine.press(CalcButtons::Clear);
+        engine.press(CalcButtons::MemoryRecall);
+        assert_eq!(engine.var.to_display(), "1.5");
+        assert_eq!(engine.var.to_decimal(), 1.5);
+    }
+
+    #[test]
+    fn check_memory_finishes_pending_sum() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::MemoryAdd] {
+            engine.press(button);
+        }
+        assert_eq!(engine.memory, 6.0);
+    }
+
+    #[test]
+    fn check_memory_subtract_recalls_negative() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num4, CalcButtons::MemorySubtract, CalcButtons::MemoryRecall] {
+            engine.press(button);
+        }
+        assert_eq!(engine.var.to_display(), "-4");
+        assert_eq!(engine.var.to_decimal(), -4.0);
+    }
+
+    #[test]
+    fn check_memory_recall_is_an_operand() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num5, CalcButtons::MemoryAdd, CalcButtons::Num2, CalcButtons::Add, CalcButtons::MemoryRecall] {
+            engine.press(button);
+        }
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "7");
+    }
+
+    #[test]
+    fn check_memory_clear() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num9, CalcButtons::MemoryAdd, CalcButtons::MemoryClear, CalcButtons::MemoryRecall] {
+            engine.press(button);
+        }
+        assert!(!engine.display().memory);
+        assert_eq!(engine.var.to_display(), "0");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_sign {
+    use super::*;
+
+    #[test]
+    fn check_negative_entry() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Decimal, CalcButtons::Num5, CalcButtons::SignToggle] {
+            engine.press(button);
+        }
+        assert_eq!(engine.display().input, "-12.5");
+        engine.press(CalcButtons::SignToggle);
+        assert_eq!(engine.display().input, "12.5");
+    }
+
+    #[test]
+    fn check_negative_operands() {
+        let mut engine = CalcEngine::new();
+        for button in [
+            CalcButtons::Num5, CalcButtons::SignToggle, CalcButtons::Add,
+            CalcButtons::Num3, CalcButtons::Multiply,
+            CalcButtons::SignToggle, CalcButtons::Num2,
+        ] {
+            engine.press(button);
+        }
+        assert_eq!(engine.display().input, "-2");
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "4");
+    }
+
+    #[test]
+    fn check_subtract_from_zero() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num0, CalcButtons::Subtract, CalcButtons::Num5] {
+            engine.press(button);
+        }
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "-5");
+    }
+
+    #[test]
+    fn check_toggle_after_sum_negates_result() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num6, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum] {
+            engine.press(button);
+        }
+        assert_eq!(engine.press(CalcButtons::SignToggle).sum, "-2");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_clearing {
+    use super::*;
+
+    #[test]
+    fn check_backspace_digits_and_decimal() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Decimal, CalcButtons::Num5] {
+            engine.press(button);
+        }
+        assert_eq!(engine.press(CalcButtons::Backspace).input, "12.");
+        assert_eq!(engine.press(CalcButtons::Backspace).input, "12");
+        assert_eq!(engine.var.decimal_index, 0);
+        engine.press(CalcButtons::Num3);
+        assert_eq!(engine.display().input, "123");
+    }
+
+    #[test]
+    fn check_backspace_ignores_results() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num4, CalcButtons::Add, CalcButtons::Num4, CalcButtons::Sum, CalcButtons::Backspace] {
+            engine.press(button);
+        }
+        assert_eq!(engine.display().sum, "8");
+        assert_eq!(engine.display().input, "4");
+    }
+
+    #[test]
+    fn check_clear_entry_keeps_pending_operator() {
+        let mut engine = CalcEngine::new();
+        for button in [
+            CalcButtons::Num9, CalcButtons::Subtract,
+            CalcButtons::Num5, CalcButtons::ClearEntry,
+        ] {
+            engine.press(button);
+        }
+        assert_eq!(engine.display().input, "0");
+        assert_eq!(engine.last_op, Some(PendingOp::Subtract));
+        engine.press(CalcButtons::Num2);
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "7");
+    }
+
+    #[test]
+    fn check_clear_entry_in_algebraic_mode() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        for button in [
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num8,
+            CalcButtons::ClearEntry, CalcButtons::Num3,
+            CalcButtons::Multiply, CalcButtons::Num2,
+        ] {
+            engine.press(button);
+        }
+        assert_eq!(engine.press(CalcButtons::Sum).sum, "8");
+    }
+
+    #[test]
+    fn check_all_clear() {
+        let mut engine = CalcEngine::new();
+        for button in [CalcButtons::Num9, CalcButtons::Subtract, CalcButtons::Num5, CalcButtons::Clear] {
+            engine.press(button);
+        }
+        assert_eq!(engine.last_op, None);
+        assert_eq!(engine.display().sum, "0");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_repeat_equals {
+    use super::*;
+
+    fn sums(engine: &mut CalcEngine, buttons: &[CalcButtons], repeats: usize) -> Vec<String> {
+        for button in buttons {
+            engine.press(button.clone());
+        }
+        let mut results: Vec<String> = Vec::new();
+        for _ in 0..repeats {
+            results.push(engine.press(CalcButtons::Sum).sum);
+        }
+        results
+    }
+
+    #[test]
+    fn check_repeat_multiply_squares() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num2, CalcButtons::Multiply], 3), ["4", "8", "16"]);
+    }
+
+    #[test]
+    fn check_repeat_add() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3], 3), ["5", "8", "11"]);
+    }
+
+    #[test]
+    fn check_repeat_subtract() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Subtract, CalcButtons::Num4], 3), ["6", "2", "-2"]);
+    }
+
+    #[test]
+    fn check_repeat_multiply() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num3, CalcButtons::Multiply, CalcButtons::Num2], 3), ["6", "12", "24"]);
+    }
+
+    #[test]
+    fn check_repeat_divide() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num8, CalcButtons::Divide, CalcButtons::Num2], 3), ["4", "2", "1"]);
+    }
+
+    #[test]
+    fn check_repeat_divide_by_self() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num4, CalcButtons::Divide], 2), ["1", "0.25"]);
+    }
+
+    #[test]
+    fn check_constant_with_new_number() {
+        let mut engine = CalcEngine::new();
+        sums(&mut engine, &[CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3], 1);
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num7], 2), ["10", "13"]);
+    }
+
+    #[test]
+    fn check_repeat_in_algebraic_mode() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num2, CalcButtons::Multiply], 3), ["4", "8", "16"]);
+        engine.press(CalcButtons::Clear);
+        assert_eq!(sums(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add,
+            CalcButtons::Num3, CalcButtons::Multiply, CalcButtons::Num4,
+        ], 2), ["14", "56"]);
+    }
+
+    #[test]
+    fn check_clear_forgets_repeat() {
+        let mut engine = CalcEngine::new();
+        sums(&mut engine, &[CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3], 1);
+        engine.press(CalcButtons::Clear);
+        assert_eq!(sums(&mut engine, &[CalcButtons::Num7], 2), ["7", "7"]);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_error_latch {
+    use super::*;
+
+    #[test]
+    fn check_divide_by_zero_latches() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num8, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+        assert_eq!(display.status_line(), "E DEG");
+    }
+
+    #[test]
+    fn check_only_clear_releases() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num8, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Sum,
+        ]);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Add, CalcButtons::ClearEntry, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+        assert_eq!(display.input, "0");
+
+        let display = engine.press(CalcButtons::Clear);
+        assert_eq!(display.error, None);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Add, CalcButtons::Num2, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "7");
+    }
+
+    #[test]
+    fn check_algebraic_divide_by_zero_latches() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Add, CalcButtons::Num2, CalcButtons::Divide,
+            CalcButtons::Num0, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_memory_overflow_latches() {
+        let mut engine = CalcEngine::new();
+        for _ in 0..20 {
+            engine.press(CalcButtons::Num9);
+        }
+        let display = press_all(&mut engine, &[
+            CalcButtons::Multiply, CalcButtons::Sum, CalcButtons::Multiply, CalcButtons::Sum,
+            CalcButtons::Multiply, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.error, Some(CalcError::Overflow)); // The input keeps six nines, and 999999^8 is past i128
+    }
+}
+
+#[cfg(test)]
+mod lib_test_scientific {
+    use super::*;
+
+    fn apply(op: UnaryOp, input: &str, angle_mode: AngleMode) -> Result<String, CalcError> {
+        op.apply(input.parse().unwrap(), 12, angle_mode).map(|res| res.to_string())
+    }
+
+    #[test]
+    fn check_second_layer_on_entry() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num9, CalcButtons::Second, CalcButtons::Subtract,
+        ]);
+        assert_eq!(display.input, "3");
+        assert!(!display.shift);
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum]);
+        assert_eq!(display.sum, "4");
+    }
+
+    #[test]
+    fn check_function_of_operand() {
+        let mut engine = CalcEngine::new();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num4,
+            CalcButtons::Second, CalcButtons::Add, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "18");
+    }
+
+    #[test]
+    fn check_function_of_result() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Sum,
+            CalcButtons::Second, CalcButtons::Num0,
+        ]);
+        assert_eq!(display.sum, "120");
+    }
+
+    #[test]
+    fn check_power_precedence() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num3,
+            CalcButtons::Second, CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "18");
+
+        engine.set_entry_mode(EntryMode::Chain);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Second, CalcButtons::Multiply, CalcButtons::Num1,
+            CalcButtons::Num0, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "1024");
+    }
+
+    #[test]
+    fn check_angle_modes() {
+        let mut engine = CalcEngine::new();
+        assert_eq!(engine.display().angle_mode, AngleMode::Degrees);
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Decimal]);
+        assert_eq!(display.angle_mode, AngleMode::Radians);
+        assert_eq!(display.status_line(), "RAD");
+
+        assert_eq!(apply(UnaryOp::Sin, "30", AngleMode::Degrees), Ok("0.5".to_string()));
+        assert_eq!(apply(UnaryOp::Sin, "180", AngleMode::Degrees), Ok("0".to_string()));
+        assert_eq!(apply(UnaryOp::Cos, "200", AngleMode::Grads), Ok("-1".to_string()));
+        assert_eq!(apply(UnaryOp::ArcTan, "1", AngleMode::Degrees), Ok("45".to_string()));
+        assert_eq!(apply(UnaryOp::ArcCos, "-1", AngleMode::Radians), Ok("3.14159265359".to_string()));
+        assert_eq!(apply(UnaryOp::Tan, "90", AngleMode::Degrees), Err(CalcError::Domain));
+    }
+
+    #[test]
+    fn check_functions() {
+        assert_eq!(apply(UnaryOp::SquareRoot, "2", AngleMode::Degrees), Ok("1.414213562373".to_string()));
+        assert_eq!(apply(UnaryOp::Square, "1.5", AngleMode::Degrees), Ok("2.25".to_string()));
+        assert_eq!(apply(UnaryOp::Reciprocal, "8", AngleMode::Degrees), Ok("0.125".to_string()));
+        assert_eq!(apply(UnaryOp::Log10, "1000", AngleMode::Degrees), Ok("3".to_string()));
+        assert_eq!(apply(UnaryOp::Ln, "1", AngleMode::Degrees), Ok("0".to_string()));
+        assert_eq!(apply(UnaryOp::Exp, "1", AngleMode::Degrees), Ok("2.718281828459".to_string()));
+        assert_eq!(apply(UnaryOp::Factorial, "20", AngleMode::Degrees), Ok("2432902008176640000".to_string()));
+    }
+
+    #[test]
+    fn check_function_errors() {
+        assert_eq!(apply(UnaryOp::SquareRoot, "-4", AngleMode::Degrees), Err(CalcError::Domain));
+        assert_eq!(apply(UnaryOp::Reciprocal, "0", AngleMode::Degrees), Err(CalcError::DivideByZero));
+        assert_eq!(apply(UnaryOp::ArcSin, "2", AngleMode::Degrees), Err(CalcError::Domain));
+        assert_eq!(apply(UnaryOp::Ln, "0", AngleMode::Degrees), Err(CalcError::Domain));
+        assert_eq!(apply(UnaryOp::Factorial, "2.5", AngleMode::Degrees), Err(CalcError::Domain));
+        assert_eq!(apply(UnaryOp::Factorial, "40", AngleMode::Degrees), Err(CalcError::Overflow));
+        assert_eq!(apply(UnaryOp::Exp, "1000", AngleMode::Degrees), Err(CalcError::Overflow));
+
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num4, CalcButtons::SignToggle, CalcButtons::Second, CalcButtons::Subtract,
+        ]);
+        assert_eq!(display.error, Some(CalcError::Domain));
+    }
+}
+
+#[cfg(test)]
+mod lib_test_programmer {
+    use super::*;
+
+    fn programmer() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.press(CalcButtons::Mode);
+        engine
+    }
+
+    #[test]
+    fn check_mode_switch_keeps_whole_sum() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num7, CalcButtons::Decimal, CalcButtons::Num9, CalcButtons::Sum,
+        ]);
+        let display = engine.press(CalcButtons::Mode);
+        assert_eq!(display.mode, CalcMode::Programmer);
+        assert_eq!(display.sum, "7");
+        assert_eq!(display.status_line(), "DEC i64");
+    }
+
+    #[test]
+    fn check_hex_entry() {
+        let mut engine = programmer();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Base, CalcButtons::Base, CalcButtons::Base, // DEC to OCT, BIN then HEX
+            CalcButtons::Second, CalcButtons::Num6, CalcButtons::Second, CalcButtons::Num6,
+            CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "100");
+
+        let display = press_all(&mut engine, &[CalcButtons::Base]);
+        assert_eq!(display.sum, "256");
+    }
+
+    #[test]
+    fn check_format_keys_only_in_programmer_mode() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Base, CalcButtons::WordSize, CalcButtons::Signed]);
+        assert_eq!(engine.press(CalcButtons::Mode).status_line(), "DEC i64");
+    }
+
+    #[test]
+    fn check_digits_outside_base_ignored() {
+        let mut engine = programmer();
+        engine.press(CalcButtons::Base);
+        engine.press(CalcButtons::Base); // BIN
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Decimal, CalcButtons::Num1,
+        ]);
+        assert_eq!(display.input, "101");
+    }
+
+    #[test]
+    fn check_word_size_wraps() {
+        let mut engine = programmer();
+        engine.press(CalcButtons::WordSize); // 8-bit
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Num7,
+            CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "-128");
+
+        let display = engine.press(CalcButtons::Signed);
+        assert_eq!(display.sum, "128");
+        assert_eq!(display.status_line(), "DEC u8");
+    }
+
+    #[test]
+    fn check_bitwise_keys() {
+        let mut engine = programmer();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num2,
+            CalcButtons::Second, CalcButtons::Num7, CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "8");
+
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Second, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "64");
+
+        engine.press(CalcButtons::WordSize);
+        engine.press(CalcButtons::Signed);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Second, CalcButtons::Num0,
+            CalcButtons::Add, CalcButtons::Num0, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "250");
+    }
+
+    #[test]
+    fn check_integer_divide_and_mod() {
+        let mut engine = programmer();
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num7, CalcButtons::Divide, CalcButtons::Num5, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "3");
+
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num7, CalcButtons::Second, CalcButtons::Multiply,
+            CalcButtons::Num5, CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "2");
+
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let sum = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Add, CalcButtons::Num7, CalcButtons::Divide, CalcButtons::Num2,
+            CalcButtons::Sum,
+        ]).sum;
+        assert_eq!(sum, "4");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_fraction {
+    use super::*;
+
+    fn fraction() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Fraction);
+        engine
+    }
+
+    #[test]
+    fn check_thirds_stay_exact() {
+        let mut engine = fraction();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "1/3");
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::Sum]);
+        assert_eq!(display.sum, "1");
+    }
+
+    #[test]
+    fn check_mixed_number() {
+        let mut engine = fraction();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "1 2/3");
+        assert_eq!(display.status_line(), "FRAC");
+
+        let display = press_all(&mut engine, &[CalcButtons::SignToggle]);
+        assert_eq!(display.sum, "-1 2/3");
+    }
+
+    #[test]
+    fn check_decimal_entry_reduces() {
+        let mut engine = fraction();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Decimal, CalcButtons::Num2, CalcButtons::Num5,
+            CalcButtons::Add, CalcButtons::Num1, CalcButtons::Divide, CalcButtons::Num6, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "5/24"); // Chain: (0.25 + 1) / 6
+    }
+
+    #[test]
+    fn check_toggle_decimal_display() {
+        let mut engine = fraction();
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        let display = engine.press(CalcButtons::FractionToggle);
+        assert_eq!(display.sum, "0.666666666667");
+        assert_eq!(display.status_line(), "FRAC D");
+
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Sum]);
+        assert_eq!(display.sum, "2/3");
+    }
+
+    #[test]
+    fn check_functions_keep_exact_when_they_can() {
+        let mut engine = fraction();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num4, CalcButtons::Divide, CalcButtons::Num9, CalcButtons::Sum,
+            CalcButtons::Second, CalcButtons::Subtract,
+        ]);
+        assert_eq!(display.sum, "2/3");
+
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Divide]);
+        assert_eq!(display.sum, "1 1/2");
+
+        let display = press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Second, CalcButtons::Subtract]);
+        assert_eq!(display.input, "1.414213562373");
+    }
+
+    #[test]
+    fn check_algebraic_fractions() {
+        let mut engine = fraction();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Add, CalcButtons::Num1, CalcButtons::Divide, CalcButtons::Num2,
+            CalcButtons::Multiply, CalcButtons::Num1, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "1 1/6");
+    }
+
+    #[test]
+    fn check_mode_switch_converts() {
+        let mut engine = fraction();
+        press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Divide, CalcButtons::Num8, CalcButtons::Sum,
+        ]);
+        engine.set_mode(CalcMode::Standard);
+        assert_eq!(engine.display().sum, "0.125");
+        engine.set_mode(CalcMode::Fraction);
+        assert_eq!(engine.display().sum, "1/8");
+    }
+
+    #[test]
+    fn check_sum_current_stays_exact() {
+        let mut sum = SumCurrent::new_from(1).unwrap();
+        sum.sum = Number::exact(sum.sum.to_decimal(12).unwrap());
+        let third = sum.divide(3).unwrap();
+        assert_eq!(third.sum.to_string(), "1/3");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_complex {
+    use super::*;
+
+    fn complex() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Complex);
+        engine
+    }
+
+    #[test]
+    fn check_entry_with_i() {
+        let mut engine = complex();
+        let display = press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::Imaginary]);
+        assert_eq!(display.input, "4i");
+        let display = press_all(&mut engine, &[CalcButtons::SignToggle]);
+        assert_eq!(display.input, "-4i");
+
+        let mut engine = complex();
+        let display = press_all(&mut engine, &[CalcButtons::Imaginary, CalcButtons::Multiply, CalcButtons::Imaginary, CalcButtons::Sum]);
+        assert_eq!(display.sum, "-1");
+    }
+
+    #[test]
+    fn check_four_operations() {
+        let mut engine = complex();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num3, CalcButtons::Add, CalcButtons::Num4, CalcButtons::Imaginary, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "3+4i");
+        assert_eq!(display.sum_parts, Some(("3".to_string(), "4".to_string())));
+        assert_eq!(display.status_line(), "CPLX DEG");
+
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Imaginary, CalcButtons::Sum]);
+        assert_eq!(display.sum, "-8+6i");
+        let display = press_all(&mut engine, &[CalcButtons::Divide, CalcButtons::Num2, CalcButtons::Imaginary, CalcButtons::Sum]);
+        assert_eq!(display.sum, "3+4i");
+        let display = press_all(&mut engine, &[CalcButtons::Subtract, CalcButtons::Num4, CalcButtons::Imaginary, CalcButtons::Sum]);
+        assert_eq!(display.sum, "3");
+    }
+
+    #[test]
+    fn check_algebraic_precedence() {
+        let mut engine = complex();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Add, CalcButtons::Num2, CalcButtons::Multiply,
+            CalcButtons::Imaginary, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "1+2i");
+    }
+
+    #[test]
+    fn check_polar_display() {
+        let mut engine = complex();
+        press_all(&mut engine, &[
+            CalcButtons::Num3, CalcButtons::Add, CalcButtons::Num4, CalcButtons::Imaginary, CalcButtons::Sum,
+        ]);
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Sum]);
+        assert_eq!(display.sum, "5∠53.130102354156");
+        assert_eq!(display.sum_parts, Some(("5".to_string(), "53.130102354156".to_string())));
+        assert_eq!(display.status_line(), "POLAR DEG");
+    }
+
+    #[test]
+    fn check_magnitude_argument_conjugate() {
+        let mut engine = complex();
+        let parts = [CalcButtons::Num3, CalcButtons::Subtract, CalcButtons::Num4, CalcButtons::Imaginary, CalcButtons::Sum];
+        press_all(&mut engine, &parts);
+        let display = press_all(&mut engine, &[CalcButtons::Conjugate]);
+        assert_eq!(display.sum, "3+4i");
+        let display = press_all(&mut engine, &[CalcButtons::Magnitude]);
+        assert_eq!(display.sum, "5");
+
+        let mut engine = complex();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Imaginary, CalcButtons::Sum]);
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num2]);
+        assert_eq!(display.sum, "90");
+    }
+
+    #[test]
+    fn check_square_root_of_negative() {
+        let mut engine = complex();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num4, CalcButtons::SignToggle, CalcButtons::SquareRoot,
+        ]);
+        assert_eq!(display.input, "2i");
+
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num4, CalcButtons::SignToggle, CalcButtons::SquareRoot,
+        ]);
+        assert_eq!(display.error, Some(CalcError::Domain));
+    }
+
+    #[test]
+    fn check_memory_holds_complex() {
+        let mut engine = complex();
+        press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Add, CalcButtons::Imaginary, CalcButtons::MemoryAdd, CalcButtons::Clear,
+        ]);
+        let display = press_all(&mut engine, &[CalcButtons::MemoryRecall]);
+        assert_eq!(display.input, "1+i");
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::MemoryRecall, CalcButtons::Sum]);
+        assert_eq!(display.sum, "2i");
+    }
+
+    #[test]
+    fn check_mode_switch_drops_complex() {
+        let mut engine = complex();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Imaginary, CalcButtons::Sum]);
+        engine.set_mode(CalcMode::Standard);
+        assert_eq!(engine.display().sum, "0");
+
+        let mut engine = complex();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Sum]);
+        engine.set_mode(CalcMode::Standard);
+        assert_eq!(engine.display().sum, "2");
+        assert_eq!(engine.display().sum_parts, None);
+    }
+
+    #[test]
+    fn check_i_outside_complex_mode() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::Imaginary]);
+        assert_eq!(display.input, "4");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_rpn {
+    use super::*;
+
+    fn rpn() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Rpn);
+        engine
+    }
+
+    fn stack(display: &DisplayState) -> [&str; 4] {
+        let stack = display.stack.as_ref().unwrap();
+        [&stack[0], &stack[1], &stack[2], &stack[3]]
+    }
+
+    #[test]
+    fn check_enter_and_operators() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num3, CalcButtons::Sum, CalcButtons::Num4, CalcButtons::Add,
+        ]);
+        assert_eq!(display.sum, "7");
+        assert_eq!(display.status_line(), "RPN DEG");
+
+        // (2 + 3) × (4 + 5)
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Sum, CalcButtons::Num3, CalcButtons::Add,
+            CalcButtons::Num4, CalcButtons::Sum, CalcButtons::Num5, CalcButtons::Add,
+            CalcButtons::Multiply,
+        ]);
+        assert_eq!(display.sum, "45");
+    }
+
+    #[test]
+    fn check_stack_shows_entry_as_x() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Sum, CalcButtons::Num2, CalcButtons::Sum,
+            CalcButtons::Num3, CalcButtons::Sum, CalcButtons::Num4,
+        ]);
+        assert_eq!(stack(&display), ["4", "3", "2", "1"]);
+    }
+
+    #[test]
+    fn check_enter_duplicates_x() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Sum, CalcButtons::Multiply,
+        ]);
+        assert_eq!(display.sum, "25");
+    }
+
+    #[test]
+    fn check_t_copies_down() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Sum, CalcButtons::Sum, CalcButtons::Sum,
+            CalcButtons::Multiply, CalcButtons::Multiply, CalcButtons::Multiply,
+        ]);
+        assert_eq!(display.sum, "16");
+        assert_eq!(stack(&display), ["16", "2", "2", "2"]);
+    }
+
+    #[test]
+    fn check_swap_roll_down_last_x() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Sum, CalcButtons::Num2,
+            CalcButtons::Swap, CalcButtons::Subtract,
+        ]);
+        assert_eq!(display.sum, "-8");
+
+        let display = press_all(&mut engine, &[CalcButtons::LastX]);
+        assert_eq!(stack(&display), ["10", "-8", "0", "0"]);
+
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Sum, CalcButtons::Num2, CalcButtons::Sum,
+            CalcButtons::Num3, CalcButtons::Sum, CalcButtons::Num4, CalcButtons::RollDown,
+        ]);
+        assert_eq!(stack(&display), ["3", "2", "1", "4"]);
+    }
+
+    #[test]
+    fn check_functions_and_sign() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num9, CalcButtons::SquareRoot, CalcButtons::SignToggle,
+        ]);
+        assert_eq!(display.sum, "-3");
+        let display = press_all(&mut engine, &[CalcButtons::LastX]);
+        assert_eq!(stack(&display), ["9", "-3", "0", "0"]);
+    }
+
+    #[test]
+    fn check_clear_x() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Sum, CalcButtons::Num7, CalcButtons::ClearEntry,
+            CalcButtons::Num2, CalcButtons::Add,
+        ]);
+        assert_eq!(display.sum, "7");
+    }
+
+    #[test]
+    fn check_errors_latch() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Sum, CalcButtons::Num0, CalcButtons::Divide,
+        ]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_mode_switch_keeps_x() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num6, CalcButtons::Multiply, CalcButtons::Num7, CalcButtons::Sum]);
+        engine.press(CalcButtons::EntryMode);
+        let display = engine.press(CalcButtons::EntryMode);
+        assert_eq!(display.entry_mode, EntryMode::Rpn);
+        assert_eq!(stack(&display), ["42", "0", "0", "0"]);
+
+        let display = press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Divide]);
+        assert_eq!(display.sum, "21");
+
+        engine.set_mode(CalcMode::Programmer);
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Divide]);
+        assert_eq!(display.sum, "4");
+
+        let display = engine.press(CalcButtons::EntryMode);
+        assert_eq!(display.entry_mode, EntryMode::Chain);
+        assert_eq!(display.sum, "4");
+        assert_eq!(display.stack, None);
+    }
+
+    #[test]
+    fn check_memory() {
+        let mut engine = rpn();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num8, CalcButtons::MemoryAdd, CalcButtons::Num2, CalcButtons::MemoryRecall,
+        ]);
+        assert_eq!(stack(&display), ["8", "2", "8", "0"]);
+        assert!(display.memory);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_screen {
+    use super::*;
+
+    #[test]
+    fn check_input_digit_limit() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Num3, CalcButtons::Num4,
+            CalcButtons::Num5, CalcButtons::Num6, CalcButtons::Num7,
+        ]);
+        assert_eq!(display.input, "123456"); // The seventh digit is dropped, not an error
+        assert_eq!(display.error, None);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Clear, CalcButtons::Decimal, CalcButtons::Num1, CalcButtons::Num2,
+            CalcButtons::Num3, CalcButtons::Num4, CalcButtons::Num5,
+        ]);
+        assert_eq!(display.input, "0.1234");
+        assert_eq!(engine.screen().input, "Input: 0.1234");
+    }
+
+    #[test]
+    fn check_wide_sum_goes_scientific() {
+        let mut engine = CalcEngine::new();
+        let nines: Vec<CalcButtons> = vec![CalcButtons::Num9; 5];
+        press_all(&mut engine, &nines);
+        press_all(&mut engine, &[CalcButtons::Multiply]);
+        press_all(&mut engine, &nines);
+        let display = press_all(&mut engine, &[CalcButtons::Sum]);
+        assert_eq!(display.error, None);
+        assert_eq!(display.sum, "9999800001");
+        assert_eq!(engine.screen().sum, "Sum: 9.9998e9"); // Does not fit eight places as it is
+    }
+
+    #[test]
+    fn check_long_fraction_rounds_to_fit() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Divide, CalcButtons::Num3, CalcButtons::Sum]);
+        assert_eq!(engine.screen().sum, "Sum: 0.666667");
+        assert_eq!(engine.error, None);
+    }
+
+    #[test]
+    fn check_rpn_stack_lines() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Rpn);
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Sum, CalcButtons::Num3]);
+        let screen = engine.screen();
+        assert!(screen.stacked);
+        assert_eq!(screen.sum, "X: 3");
+        assert_eq!(screen.input, "T: 0\nZ: 0\nY: 2");
+        let eights: Vec<CalcButtons> = vec![CalcButtons::Num8; 9];
+        let display = press_all(&mut engine, &eights);
+        assert_eq!(display.stack.unwrap()[0], "38888888"); // X has the sum's eight places
+    }
+}
+
+#[cfg(test)]
+mod lib_test_convert {
+    use super::*;
+    use calc_sim::csv::CsvTable;
+    use calc_sim::units::{Converter, UnitTable, UNITS_PATH};
+
+    fn convert() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.converter = Converter::new(UnitTable::load(UNITS_PATH).unwrap());
+        engine.set_mode(CalcMode::Convert);
+        engine
+    }
+
+    #[test]
+    fn check_convert_entry() {
+        let mut engine = convert();
+        assert_eq!(engine.display().status_line(), "m>km");
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Num5, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Convert,
+        ]);
+        assert_eq!(display.input, "2.5");
+
+        // The result is an operand like any other
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum]);
+        assert_eq!(display.sum, "3.5");
+    }
+
+    #[test]
+    fn check_keypad_shortcuts() {
+        let mut engine = convert();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Second, CalcButtons::Num1, CalcButtons::Second, CalcButtons::Num1,
+            CalcButtons::Second, CalcButtons::Num1,
+        ]);
+        assert_eq!(display.status_line(), "K>C");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num2, CalcButtons::Second, CalcButtons::Num3]);
+        assert_eq!(display.status_line(), "C>F");
+
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num3, CalcButtons::Num7, CalcButtons::Second, CalcButtons::Num0,
+        ]);
+        assert_eq!(display.input, "98.6");
+
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Sum, CalcButtons::Second, CalcButtons::Num0]);
+        assert_eq!(display.status_line(), "F>C");
+        assert_eq!(display.input, "37");
+    }
+
+    #[test]
+    fn check_converts_sum_after_equals() {
+        let mut engine = convert();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num5, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Sum]);
+        let display = engine.press(CalcButtons::Convert);
+        assert_eq!(display.sum, "1");
+    }
+
+    #[test]
+    fn check_no_table_is_an_error() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Convert);
+        let display = press_all(&mut engine, &[CalcButtons::Num1, CalcButtons::Convert]);
+        assert_eq!(display.error, Some(CalcError::InvalidInput));
+    }
+}
+
+#[cfg(test)]
+mod lib_test_currency {
+    use super::*;
+    use calc_sim::csv::CsvTable;
+    use calc_sim::currency::{CurrencyConverter, RateTable};
+
+    fn currency() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        let table = RateTable::parse("base,USD\nEUR,0.8,2026-10-16 16:00 UTC\nGBP,0.5,2026-10-16 16:00 UTC\n").unwrap();
+        engine.currency = CurrencyConverter::new(table);
+        engine.set_mode(CalcMode::Currency);
+        engine
+    }
+
+    #[test]
+    fn check_convert_entry() {
+        let mut engine = currency();
+        assert_eq!(engine.display().status_line(), "USD>EUR");
+        let display = press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Num5, CalcButtons::Convert]);
+        assert_eq!(display.input, "20");
+    }
+
+    #[test]
+    fn check_cross_rate_from_keypad() {
+        let mut engine = currency();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Second, CalcButtons::Num2, CalcButtons::Second, CalcButtons::Num3,
+        ]);
+        assert_eq!(display.status_line(), "EUR>GBP");
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num4, CalcButtons::Second, CalcButtons::Num0,
+        ]);
+        assert_eq!(display.input, "2.5");
+    }
+
+    #[test]
+    fn check_missing_rate_latches() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Currency);
+        let display = press_all(&mut engine, &[CalcButtons::Num1, CalcButtons::Convert]);
+        assert_eq!(display.error, Some(CalcError::NoRate));
+        assert_eq!(CalcError::NoRate.label(), "No Rate");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_stats {
+    use super::*;
+
+    fn stats() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Stats);
+        engine
+    }
+
+    #[test]
+    fn check_add_and_mean() {
+        let mut engine = stats();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::StatAdd,
+            CalcButtons::Num4, CalcButtons::StatAdd,
+            CalcButtons::Num9, CalcButtons::StatAdd,
+        ]);
+        assert_eq!(display.sum, "3");
+        assert_eq!(display.status_line(), "STAT n=3");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num2]);
+        assert_eq!(display.sum, "5");
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Sum]);
+        assert_eq!(display.sum, "10"); // The mean carries on as a result
+    }
+
+    #[test]
+    fn check_remove_sample() {
+        let mut engine = stats();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::StatAdd,
+            CalcButtons::Num7, CalcButtons::StatAdd,
+            CalcButtons::Num2, CalcButtons::Second, CalcButtons::Num0,
+        ]);
+        assert_eq!(display.sum, "1");
+        let display = press_all(&mut engine, &[CalcButtons::StatSum]);
+        assert_eq!(display.sum, "7");
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::StatRemove]);
+        assert_eq!(display.error, Some(CalcError::InvalidInput));
+    }
+
+    #[test]
+    fn check_pairs_and_regression() {
+        let mut engine = stats();
+        for (x, y) in [(CalcButtons::Num1, CalcButtons::Num3), (CalcButtons::Num2, CalcButtons::Num5), (CalcButtons::Num3, CalcButtons::Num7)] {
+            press_all(&mut engine, &[x, CalcButtons::StatPair]);
+            assert_eq!(engine.display().status_line(), format!("STAT n={} XY", engine.stats.samples.len()));
+            press_all(&mut engine, &[y, CalcButtons::StatAdd]);
+        }
+        assert_eq!(press_all(&mut engine, &[CalcButtons::StatSlope]).sum, "2");
+        assert_eq!(press_all(&mut engine, &[CalcButtons::StatIntercept]).sum, "1");
+        assert_eq!(press_all(&mut engine, &[CalcButtons::StatCorrelation]).sum, "1");
+    }
+
+    #[test]
+    fn check_review_and_edit() {
+        let mut engine = stats();
+        press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::StatAdd,
+            CalcButtons::Num2, CalcButtons::StatAdd,
+            CalcButtons::Num3, CalcButtons::StatAdd,
+        ]);
+        let display = press_all(&mut engine, &[CalcButtons::StatReviewNext, CalcButtons::StatReviewNext]);
+        assert_eq!(display.review, Some("#2 2".to_string()));
+        assert_eq!(display.status_line(), "STAT #2/3");
+        let display = press_all(&mut engine, &[CalcButtons::Num8]);
+        assert_eq!(display.review, None); // The replacement shows while it is typed
+        let display = press_all(&mut engine, &[CalcButtons::StatAdd, CalcButtons::StatSum]);
+        assert_eq!(display.sum, "12");
+        press_all(&mut engine, &[CalcButtons::StatReviewPrev, CalcButtons::StatRemove]);
+        assert_eq!(press_all(&mut engine, &[CalcButtons::StatCount]).sum, "2");
+    }
+
+    #[test]
+    fn check_samples_survive_clear() {
+        let mut engine = stats();
+        press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::StatAdd, CalcButtons::Clear]);
+        assert_eq!(press_all(&mut engine, &[CalcButtons::StatCount]).sum, "1");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Divide, CalcButtons::StatCount]);
+        assert_eq!(display.sum, "0");
+    }
+
+    #[test]
+    fn check_rpn_entry() {
+        let mut engine = stats();
+        engine.set_entry_mode(EntryMode::Rpn);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num6, CalcButtons::StatAdd,
+            CalcButtons::Num8, CalcButtons::StatAdd,
+        ]);
+        assert_eq!(display.stack.unwrap()[0], "2");
+        let display = press_all(&mut engine, &[CalcButtons::StatMean]);
+        let stack = display.stack.unwrap();
+        assert_eq!((stack[0].as_str(), stack[1].as_str()), ("7", "0")); // Like ENTER, Σ+ leaves the count to be replaced
+        let display = press_all(&mut engine, &[CalcButtons::StatSum]);
+        let stack = display.stack.unwrap();
+        assert_eq!((stack[0].as_str(), stack[1].as_str()), ("14", "7"));
+    }
+
+    #[test]
+    fn check_keys_only_in_stats_mode() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::StatAdd, CalcButtons::StatPair]);
+        assert_eq!(display.input, "4"); // Ignored, the entry is still being typed
+        assert!(engine.stats.samples.is_empty());
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Num2, CalcButtons::StatClear, CalcButtons::Sum]);
+        assert_eq!(display.sum, "6");
+        engine.set_mode(CalcMode::Stats);
+        press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::StatAdd]);
+        assert_eq!(engine.stats.samples.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_history {
+    use super::*;
+
+    fn tapes(engine: &CalcEngine) -> Vec<String> {
+        engine.history.entries.iter().map(|calculation| calculation.tape.clone()).collect()
+    }
+
+    #[test]
+    fn check_chain_tape() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Subtract, CalcButtons::Num3,
+            CalcButtons::Multiply, CalcButtons::Num4, CalcButtons::Sum,
+        ]);
+        assert_eq!(tapes(&engine), ["2 - 3 × 4 = -4"]);
+        let calculation = &engine.history.entries[0];
+        assert_eq!(calculation.operators(), [PendingOp::Subtract, PendingOp::Multiply]);
+        assert_eq!(calculation.result, -4.0);
+        press_all(&mut engine, &[CalcButtons::Sum, CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum]);
+        assert_eq!(tapes(&engine)[1..], ["-4 × 4 = -16", "-16 + 1 = -15"]);
+    }
+
+    #[test]
+    fn check_chain_groups() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::OpenParen, CalcButtons::Num3, CalcButtons::Add,
+            CalcButtons::Num4, CalcButtons::Sum,
+        ]);
+        assert_eq!(tapes(&engine), ["2 × (3 + 4) = 14"]);
+    }
+
+    #[test]
+    fn check_algebraic_tape() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Multiply,
+            CalcButtons::OpenParen, CalcButtons::Num4, CalcButtons::Subtract, CalcButtons::Num1, CalcButtons::Sum,
+        ]);
+        assert_eq!(tapes(&engine), ["2 + 3 × (4 - 1) = 11"]);
+    }
+
+    #[test]
+    fn check_rpn_tape() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Rpn);
+        press_all(&mut engine, &[
+            CalcButtons::Num6, CalcButtons::Sum, CalcButtons::Num3, CalcButtons::Divide,
+            CalcButtons::Num5, CalcButtons::Multiply,
+        ]);
+        assert_eq!(tapes(&engine), ["6 ÷ 3 = 2", "2 × 5 = 10"]);
+    }
+
+    #[test]
+    fn check_errors_and_lone_numbers_skipped() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Sum]);
+        press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Sum]);
+        assert!(engine.history.entries.is_empty());
+    }
+
+    #[test]
+    fn check_recall_result() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num7, CalcButtons::Multiply, CalcButtons::Num6, CalcButtons::Sum]);
+        press_all(&mut engine, &[CalcButtons::Clear, CalcButtons::Num1, CalcButtons::Add]);
+        let display = engine.recall_history(0);
+        assert_eq!(display.input, "42");
+        let display = press_all(&mut engine, &[CalcButtons::Sum]);
+        assert_eq!(display.sum, "43");
+        assert_eq!(tapes(&engine), ["7 × 6 = 42", "1 + 42 = 43"]);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_undo {
+    use super::*;
+
+    #[test]
+    fn check_undo_after_sum() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Multiply, CalcButtons::Num4,
+        ]);
+        let before = engine.display();
+        let display = press_all(&mut engine, &[CalcButtons::Sum]);
+        assert_eq!(display.sum, "20");
+        let display = press_all(&mut engine, &[CalcButtons::Undo]);
+        assert_eq!(display, before);
+        assert_eq!((display.input.as_str(), display.sum.as_str()), ("4", "5"));
+        // The chain picks up where it was, with the pending × still waiting
+        let display = press_all(&mut engine, &[CalcButtons::Backspace, CalcButtons::Num2, CalcButtons::Sum]);
+        assert_eq!(display.sum, "10");
+    }
+
+    #[test]
+    fn check_undo_after_sum_algebraic() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Multiply, CalcButtons::Num4, CalcButtons::Sum,
+        ]);
+        assert_eq!(engine.display().sum, "14");
+        press_all(&mut engine, &[CalcButtons::Undo]);
+        assert_eq!(engine.expression.len(), 4); // 2 + 3 ×, with 4 still the entry
+        let display = press_all(&mut engine, &[CalcButtons::Num0, CalcButtons::Sum]);
+        assert_eq!(display.sum, "122");
+    }
+
+    #[test]
+    fn check_redo() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num9, CalcButtons::Subtract, CalcButtons::Num4, CalcButtons::Sum]);
+        let done = engine.display();
+        let display = press_all(&mut engine, &[CalcButtons::Undo, CalcButtons::Undo]);
+        assert_eq!((display.input.as_str(), display.sum.as_str()), ("0", "9")); // Back to "9 -"
+        let display = press_all(&mut engine, &[CalcButtons::Redo, CalcButtons::Redo]);
+        assert_eq!(display, done);
+        let display = press_all(&mut engine, &[CalcButtons::Redo]);
+        assert_eq!(display, done); // Nothing further to redo
+    }
+
+    #[test]
+    fn check_press_drops_redo() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num1, CalcButtons::Num2, CalcButtons::Undo, CalcButtons::Num5]);
+        let display = press_all(&mut engine, &[CalcButtons::Redo]);
+        assert_eq!(display.input, "15");
+    }
+
+    #[test]
+    fn check_undo_holds_error() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num8, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Sum]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+        let display = press_all(&mut engine, &[CalcButtons::Undo, CalcButtons::Redo]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero)); // Only Clear gets out
+        let display = press_all(&mut engine, &[CalcButtons::Clear, CalcButtons::Num2]);
+        assert_eq!(display.error, None);
+        assert_eq!(display.input, "2");
+    }
+
+    #[test]
+    fn check_unchanged_press_not_recorded() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num7, CalcButtons::Backspace, CalcButtons::Backspace]);
+        assert_eq!(engine.undo.depth(), (2, 0)); // The second backspace had nothing to remove
+    }
+}
+
+#[cfg(test)]
+mod lib_test_constants {
+    use super::*;
+    use calc_sim::constants::{ConstantPicker, ConstantTable, CONSTANTS_PATH};
+    use calc_sim::csv::CsvTable;
+    use calc_sim::decimal::{Decimal, DEFAULT_PRECISION};
+
+    fn constants() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        let table = ConstantTable::parse("pi,π,3.1415926535897932384626433833\ngolden ratio,φ,1.6180339887498948482045868344\nspeed of light,c,299792458,m/s\n").unwrap();
+        engine.constants = ConstantPicker::new(table);
+        engine
+    }
+
+    #[test]
+    fn check_pick_from_keypad() {
+        let mut engine = constants();
+        let display = press_all(&mut engine, &[CalcButtons::Constant, CalcButtons::Subtract]);
+        assert_eq!(display.picker, Some("2/3 φ 1.6180339887498948482045868344".to_string()));
+        assert_eq!(display.status_line(), "CONST golden ratio");
+        let display = press_all(&mut engine, &[CalcButtons::Sum]);
+        assert_eq!(display.picker, None);
+        assert_eq!(display.input, "1.6180339887498948482045868344"); // Every digit in the file
+        assert_eq!(display.status_line(), "CONST golden ratio");
+        let display = press_all(&mut engine, &[CalcButtons::Add]);
+        assert_eq!(display.status_line(), "DEG"); // The name goes with the next press
+    }
+
+    #[test]
+    fn check_constant_as_operand() {
+        let mut engine = constants();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Constant, CalcButtons::Num1, CalcButtons::Sum, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "6.28318530718");
+        let display = press_all(&mut engine, &[
+            CalcButtons::Constant, CalcButtons::Num2, CalcButtons::Sum, CalcButtons::Add, CalcButtons::Num1, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "2.6180339887498948482045868344"); // Addition keeps every digit
+        assert_eq!(engine.screen().sum, "Sum: 2.618034"); // Rounded to the screen, not overflowed
+        let display = press_all(&mut engine, &[CalcButtons::Constant, CalcButtons::Num3, CalcButtons::Sum, CalcButtons::Add]);
+        assert_eq!(display.error, None);
+        assert_eq!(display.sum, "299792458");
+        assert_eq!(engine.screen().sum, "Sum: 2.9979e8"); // Nine places go to scientific notation
+    }
+
+    #[test]
+    fn check_every_shipped_constant_computes() {
+        let table = ConstantTable::load(CONSTANTS_PATH).unwrap();
+        for (index, constant) in table.constants.iter().enumerate() {
+            let sums: [(Vec<CalcButtons>, Decimal); 3] = [
+                (vec![CalcButtons::Num2, CalcButtons::Multiply], constant.value.checked_mul(Decimal::from(2), DEFAULT_PRECISION).unwrap()),
+                (vec![CalcButtons::Num1, CalcButtons::Divide], Decimal::ONE.checked_div(constant.value, DEFAULT_PRECISION).unwrap()),
+                (vec![CalcButtons::Num1, CalcButtons::Add], constant.value.checked_add(Decimal::ONE).unwrap()),
+            ];
+            for (keys, expected) in sums {
+                let mut engine = CalcEngine::new();
+                engine.constants = ConstantPicker::new(table.clone());
+                engine.constants.selected = index;
+                press_all(&mut engine, &keys);
+                let display = press_all(&mut engine, &[CalcButtons::Constant, CalcButtons::Sum, CalcButtons::Sum]);
+                assert_eq!(display.error, None, "{} after {:?}", constant.symbol, keys);
+                assert!(!expected.is_zero(), "{} after {:?}", constant.symbol, keys);
+                assert_eq!(display.sum, expected.to_string(), "{} after {:?}", constant.symbol, keys);
+                assert!(!engine.screen().sum.starts_with("E"), "{} after {:?}", constant.symbol, keys);
+            }
+        }
+    }
+
+    #[test]
+    fn check_close_without_picking() {
+        let mut engine = constants();
+        let display = press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::Constant, CalcButtons::Num2, CalcButtons::Clear]);
+        assert_eq!(display.input, "4"); // AC only closes the list
+        assert_eq!(display.constant, None);
+        let display = press_all(&mut engine, &[CalcButtons::Constant]);
+        assert_eq!(display.constant, Some("golden ratio".to_string())); // Reopens where it was
+    }
+
+    #[test]
+    fn check_typing_replaces_constant() {
+        let mut engine = constants();
+        let display = press_all(&mut engine, &[CalcButtons::Constant, CalcButtons::Sum, CalcButtons::Num5]);
+        assert_eq!(display.input, "5");
+    }
+
+    #[test]
+    fn check_rpn_lifts() {
+        let mut engine = constants();
+        engine.set_entry_mode(EntryMode::Rpn);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Constant, CalcButtons::Sum, CalcButtons::Multiply,
+        ]);
+        let stack = display.stack.unwrap();
+        assert_eq!((stack[0].as_str(), stack[1].as_str()), ("6.28318530718", "0"));
+    }
+
+    #[test]
+    fn check_programmer_truncates() {
+        let mut engine = constants();
+        engine.set_mode(CalcMode::Programmer);
+        let display = press_all(&mut engine, &[CalcButtons::Constant, CalcButtons::Num1, CalcButtons::Sum]);
+        assert_eq!(display.input, "3");
+    }
+
+    #[test]
+    fn check_undo_insert() {
+        let mut engine = constants();
+        press_all(&mut engine, &[CalcButtons::Num7, CalcButtons::Constant, CalcButtons::Sum]);
+        let display = press_all(&mut engine, &[CalcButtons::Undo]);
+        assert_eq!(display.input, "7");
+    }
+}
+
+#[cfg(test)]
+mod lib_test_registers {
+    use super::*;
+    use calc_sim::RegisterAction;
+
+    #[test]
+    fn check_store_and_recall() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num4, CalcButtons::Num2, CalcButtons::Store]);
+        assert_eq!(display.browse, Some(RegisterAction::Store));
+        assert_eq!(display.register, Some("A: 0".to_string()));
+        assert_eq!(display.status_line(), "STO DEG");
+        let display = press_all(&mut engine, &[CalcButtons::Register(1)]);
+        assert_eq!((display.browse, display.input.as_str()), (None, "42")); // The entry stays
+        let display = press_all(&mut engine, &[
+            CalcButtons::Clear, CalcButtons::Num8, CalcButtons::Add, CalcButtons::Recall, CalcButtons::Register(1), CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "50");
+    }
+
+    #[test]
+    fn check_browse_from_keypad() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num7, CalcButtons::Store, CalcButtons::Add, CalcButtons::Sum]);
+        assert_eq!(engine.registers.values[25], 7.0); // Back from A wraps to Z
+        let display = press_all(&mut engine, &[CalcButtons::Recall, CalcButtons::Subtract]);
+        assert_eq!(display.register, Some("A: 0".to_string()));
+        let display = press_all(&mut engine, &[CalcButtons::Add]);
+        assert_eq!(display.register, Some("Z: 7".to_string()));
+        let display = press_all(&mut engine, &[CalcButtons::Sum, CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::Sum]);
+        assert_eq!(display.sum, "21");
+    }
+
+    #[test]
+    fn check_store_result() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num6, CalcButtons::Multiply, CalcButtons::Num7, CalcButtons::Sum, CalcButtons::Store, CalcButtons::Register(2),
+        ]);
+        assert_eq!(engine.registers.values[2], 42.0);
+    }
+
+    #[test]
+    fn check_cancel_and_ignored_keys() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Store, CalcButtons::Num9, CalcButtons::Clear]);
+        assert_eq!((display.browse, display.input.as_str()), (None, "5")); // AC only cancels STO
+        let display = press_all(&mut engine, &[CalcButtons::Store, CalcButtons::Recall]);
+        assert_eq!(display.browse, Some(RegisterAction::Recall));
+    }
+
+    #[test]
+    fn check_kept_through_clear_and_modes() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num9, CalcButtons::Store, CalcButtons::Register(0), CalcButtons::Clear]);
+        engine.set_mode(CalcMode::Fraction);
+        let display = press_all(&mut engine, &[CalcButtons::Recall, CalcButtons::Register(0)]);
+        assert_eq!(display.input, "9");
+    }
+
+    #[test]
+    fn check_rpn_recall_lifts() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Rpn);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num3, CalcButtons::Store, CalcButtons::Register(0),
+            CalcButtons::Num4, CalcButtons::Recall, CalcButtons::Register(0), CalcButtons::Multiply,
+        ]);
+        let stack = display.stack.unwrap();
+        assert_eq!((stack[0].as_str(), stack[1].as_str()), ("12", "3"));
+    }
+
+    #[test]
+    fn check_undo_store() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Store, CalcButtons::Register(3), CalcButtons::Undo]);
+        assert_eq!(engine.registers.values[3], 0.0);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_programs {
+    use super::*;
+    use calc_sim::program::Step;
+
+    fn run(engine: &mut CalcEngine) -> DisplayState { // As the runner system does, a key at a time
+        while let Some(button) = engine.next_program_key() {
+            engine.press(button);
+        }
+        engine.display()
+    }
+
+    #[test]
+    fn check_record_without_working_out() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Program, CalcButtons::Num1]);
+        assert_eq!(display.program, Some("1-00".to_string()));
+        assert_eq!(display.status_line(), "PRGM 1 DEG");
+        let display = press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::Sum]);
+        assert_eq!(display.program, Some("1-04 Sum".to_string()));
+        assert_eq!((display.input.as_str(), display.sum.as_str()), ("0", "0"));
+        let display = press_all(&mut engine, &[CalcButtons::Program]);
+        assert_eq!(display.program, None);
+        assert!(engine.programs.unsaved);
+    }
+
+    #[test]
+    fn check_run_program() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Program, CalcButtons::Num0, CalcButtons::Num2, CalcButtons::Multiply,
+            CalcButtons::Num2, CalcButtons::Num1, CalcButtons::Sum, CalcButtons::Program,
+        ]);
+        press_all(&mut engine, &[CalcButtons::RunStop]);
+        assert_eq!(run(&mut engine).sum, "42");
+        press_all(&mut engine, &[CalcButtons::Clear, CalcButtons::Execute, CalcButtons::Num0]);
+        assert_eq!(run(&mut engine).sum, "42");
+    }
+
+    #[test]
+    fn check_countdown_loop() {
+        // 3, then LBL 1: subtract 1 while x≠0?
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Program, CalcButtons::Num5, CalcButtons::Num3, CalcButtons::Sum,
+            CalcButtons::Label, CalcButtons::Num1, CalcButtons::Subtract, CalcButtons::Num1, CalcButtons::Sum,
+            CalcButtons::Test, CalcButtons::Num2, CalcButtons::Goto, CalcButtons::Num1, CalcButtons::Program,
+        ]);
+        assert_eq!(engine.programs.slots[5][2], Step::Label(1));
+        press_all(&mut engine, &[CalcButtons::RunStop]);
+        let display = run(&mut engine);
+        assert_eq!(display.sum, "0");
+        assert_eq!(engine.programs.run, None); // Ran off the end once x reached 0
+    }
+
+    #[test]
+    fn check_store_register_step() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Program, CalcButtons::Num0, CalcButtons::Num9, CalcButtons::Store]);
+        assert!(engine.wants_register());
+        press_all(&mut engine, &[CalcButtons::Register(2), CalcButtons::Program, CalcButtons::RunStop]);
+        run(&mut engine);
+        assert_eq!(engine.registers.values[2], 9.0);
+    }
+
+    #[test]
+    fn check_single_step() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Program, CalcButtons::Num0, CalcButtons::Num4, CalcButtons::Add, CalcButtons::Num5, CalcButtons::Sum,
+            CalcButtons::Program, CalcButtons::SingleStep,
+        ]);
+        assert_eq!(run(&mut engine).input, "4");
+        let display = press_all(&mut engine, &[CalcButtons::SingleStep]);
+        assert_eq!(display.status_line(), "RUN 0 DEG");
+        let display = run(&mut engine);
+        assert_eq!(display.status_line(), "0-03 DEG");
+        press_all(&mut engine, &[CalcButtons::RunStop]);
+        assert_eq!(run(&mut engine).sum, "9");
+    }
+
+    #[test]
+    fn check_missing_label() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Program, CalcButtons::Num0, CalcButtons::Goto, CalcButtons::Num7, CalcButtons::Program, CalcButtons::RunStop,
+        ]);
+        let display = run(&mut engine);
+        assert_eq!(display.error, Some(CalcError::InvalidInput));
+        assert_eq!(engine.programs.run, None);
+    }
+
+    #[test]
+    fn check_waiting_keys() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Execute]);
+        assert_eq!(display.program, Some("XEQ _".to_string()));
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Clear]);
+        assert_eq!(display.program, None);
+        let display = press_all(&mut engine, &[CalcButtons::Label, CalcButtons::Num3]);
+        assert_eq!(display.input, "3"); // LBL outside a program does nothing
+    }
+}
+
+#[cfg(test)]
+mod lib_test_finance {
+    use super::*;
+    use calc_sim::decimal::Decimal;
+
+    fn finance() -> CalcEngine {
+        let mut engine = CalcEngine::with_precision(2);
+        engine.set_mode(CalcMode::Finance);
+        engine
+    }
+
+    #[test]
+    fn check_store_and_solve() {
+        // 10 yearly periods at 5% on -1000, then FV with nothing typed solves
+        let mut engine = finance();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Second, CalcButtons::Num6,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num1,
+            CalcButtons::Num5, CalcButtons::Second, CalcButtons::Num2,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::SignToggle,
+            CalcButtons::Second, CalcButtons::Num3,
+        ]);
+        assert_eq!(display.sum, "-1000");
+        assert_eq!(display.status_line(), "FIN PV END 1");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num5]);
+        assert_eq!(display.sum, "1628.89");
+        assert_eq!(display.status_line(), "FIN FV END 1");
+    }
+
+    #[test]
+    fn check_result_as_operand() {
+        let mut engine = finance();
+        engine.tvm.per_year = 1;
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Second, CalcButtons::Num1,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num3,
+            CalcButtons::Second, CalcButtons::Num4,
+        ]);
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::Num2, CalcButtons::Sum]);
+        assert_eq!(display.sum, "-100");
+    }
+
+    #[test]
+    fn check_begin_toggle() {
+        let mut engine = finance();
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num7]);
+        assert_eq!(display.status_line(), "FIN BGN 12");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num6]);
+        assert_eq!(display.sum, "12"); // P/Y with nothing typed shows it
+    }
+
+    #[test]
+    fn check_no_solution_latches() {
+        let mut engine = finance();
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num2]);
+        assert_eq!(display.error, Some(CalcError::NoSolution));
+        assert_eq!(CalcError::NoSolution.label(), "No Soln");
+        let display = press_all(&mut engine, &[CalcButtons::Undo]);
+        assert_eq!(display.error, Some(CalcError::NoSolution));
+        let display = press_all(&mut engine, &[CalcButtons::Clear]);
+        assert_eq!(display.error, None);
+    }
+
+    #[test]
+    fn check_bad_per_year() {
+        let mut engine = finance();
+        let display = press_all(&mut engine, &[CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num6]);
+        assert_eq!(display.error, Some(CalcError::Domain));
+        assert_eq!(engine.tvm.per_year, 12);
+    }
+
+    #[test]
+    fn check_amortization_viewer() {
+        let mut engine = finance();
+        engine.tvm.per_year = 1;
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Second, CalcButtons::Num1,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num2,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num3,
+            CalcButtons::Second, CalcButtons::Num4,
+        ]);
+        assert_eq!(engine.display().sum, "-57.62");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num8]);
+        assert_eq!(display.amort, Some("P1 INT -10".to_string()));
+        let display = press_all(&mut engine, &[CalcButtons::Subtract, CalcButtons::Subtract, CalcButtons::Sum, CalcButtons::Sum]);
+        assert_eq!(display.amort, Some("P2 BAL 0".to_string())); // Stops at the last period
+        let display = press_all(&mut engine, &[CalcButtons::Clear]);
+        assert_eq!((display.amort, display.sum.as_str()), (None, "-57.62"));
+    }
+
+    #[test]
+    fn check_mode_cycle() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Stats);
+        assert_eq!(engine.press(CalcButtons::Mode).mode, CalcMode::Finance);
+        assert_eq!(engine.press(CalcButtons::Mode).mode, CalcMode::Business);
+    }
+
+    #[test]
+    fn check_keys_only_in_finance_mode() {
+        let mut engine = CalcEngine::with_precision(2);
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::TvmPeriods, CalcButtons::TvmPerYear, CalcButtons::TvmBegin]);
+        assert_eq!(display.input, "5"); // Ignored, the entry is still being typed
+        assert_eq!((engine.tvm.periods, engine.tvm.per_year, engine.tvm.begin), (Decimal::ZERO, 12, false));
+        press_all(&mut engine, &[CalcButtons::Clear]);
+        engine.set_mode(CalcMode::Finance);
+        press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::TvmPeriods]);
+        assert_eq!(engine.tvm.periods, Decimal::from(5));
+    }
+}
+
+#[cfg(test)]
+mod lib_test_business {
+    use super::*;
+
+    fn business() -> CalcEngine {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Business);
+        engine
+    }
+
+    #[test]
+    fn check_tax_keys() {
+        let mut engine = business();
+        let display = press_all(&mut engine, &[CalcButtons::Num8, CalcButtons::Second, CalcButtons::Num3]);
+        assert_eq!(display.status_line(), "BIZ TAX 8%");
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num1]);
+        assert_eq!(display.sum, "54");
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num2]);
+        assert_eq!(display.sum, "50");
+    }
+
+    #[test]
+    fn check_grand_total() {
+        let mut engine = business();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::Sum,
+            CalcButtons::Num4, CalcButtons::Add, CalcButtons::Num6, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.status_line(), "GT BIZ TAX 0%");
+        let display = press_all(&mut engine, &[CalcButtons::Clear, CalcButtons::Second, CalcButtons::Num9]);
+        assert_eq!(display.input, "16"); // Kept through AC
+        let display = press_all(&mut engine, &[CalcButtons::Divide, CalcButtons::Num4, CalcButtons::Sum]);
+        assert_eq!(display.sum, "4");
+        assert_eq!(engine.business.grand_total, 20.0);
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num0]);
+        assert!(!display.grand_total);
+    }
+
+    #[test]
+    fn check_grand_total_only_in_business() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[CalcButtons::Num2, CalcButtons::Add, CalcButtons::Num3, CalcButtons::Sum]);
+        assert_eq!(engine.business.grand_total, 0.0);
+    }
+
+    #[test]
+    fn check_markup_and_change() {
+        let mut engine = business();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num7,
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "125");
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num8, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num8,
+            CalcButtons::Num6, CalcButtons::Num0, CalcButtons::Sum,
+        ]);
+        assert_eq!(display.sum, "-25");
+    }
+
+    #[test]
+    fn check_cost_sell_margin() {
+        let mut engine = business();
+        press_all(&mut engine, &[
+            CalcButtons::Num6, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num4,
+            CalcButtons::Num4, CalcButtons::Num0, CalcButtons::Second, CalcButtons::Num6,
+        ]);
+        let display = press_all(&mut engine, &[CalcButtons::Second, CalcButtons::Num5]);
+        assert_eq!(display.sum, "100");
+        assert_eq!(display.status_line(), "BIZ SEL TAX 0%");
+    }
+
+    #[test]
+    fn check_undo_clear_grand_total() {
+        let mut engine = business();
+        press_all(&mut engine, &[CalcButtons::Num7, CalcButtons::Sum, CalcButtons::Second, CalcButtons::Num0]);
+        assert_eq!(engine.business.grand_total, 0.0);
+        press_all(&mut engine, &[CalcButtons::Undo]);
+        assert_eq!(engine.business.grand_total, 7.0);
+    }
+
+    #[test]
+    fn check_keys_only_in_business_mode() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num8, CalcButtons::TaxRate, CalcButtons::TaxAdd, CalcButtons::GrandTotal]);
+        assert_eq!(display.input, "8"); // Ignored, the entry is still being typed
+        assert_eq!(engine.business.tax_rate, 0.0);
+        assert_eq!(engine.business.grand_total, 0.0);
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Num2, CalcButtons::Markup, CalcButtons::PercentChange, CalcButtons::Sum]);
+        assert_eq!(display.sum, "10");
+        engine.set_mode(CalcMode::Business);
+        press_all(&mut engine, &[CalcButtons::Num8, CalcButtons::TaxRate]);
+        assert_eq!(engine.business.tax_rate, 8.0);
+    }
+}
+
+#[cfg(test)]
+mod lib_test_percent {
+    use super::*;
+
+    fn percent(entry_mode: EntryMode, op: CalcButtons) -> DisplayState { // 200 op 15 %
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(entry_mode);
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, op,
+            CalcButtons::Num1, CalcButtons::Num5, CalcButtons::Percent,
+        ])
+    }
+
+    #[test]
+    fn check_chain_add_subtract() {
+        assert_eq!(percent(EntryMode::Chain, CalcButtons::Add).sum, "230");
+        assert_eq!(percent(EntryMode::Chain, CalcButtons::Subtract).sum, "170");
+    }
+
+    #[test]
+    fn check_chain_multiply_divide() {
+        assert_eq!(percent(EntryMode::Chain, CalcButtons::Multiply).sum, "30");
+        assert!(percent(EntryMode::Chain, CalcButtons::Divide).sum.starts_with("1333.333"));
+    }
+
+    #[test]
+    fn check_algebraic_each_operator() {
+        assert_eq!(percent(EntryMode::Algebraic, CalcButtons::Add).sum, "230");
+        assert_eq!(percent(EntryMode::Algebraic, CalcButtons::Subtract).sum, "170");
+        assert_eq!(percent(EntryMode::Algebraic, CalcButtons::Multiply).sum, "30");
+        assert!(percent(EntryMode::Algebraic, CalcButtons::Divide).sum.starts_with("1333.333"));
+    }
+
+    #[test]
+    fn check_algebraic_precedence() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Algebraic);
+        let display = press_all(&mut engine, &[ // 10 + 2 × 50%, the × takes the percentage
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Add, CalcButtons::Num2, CalcButtons::Multiply,
+            CalcButtons::Num5, CalcButtons::Num0, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.sum, "11");
+        let display = press_all(&mut engine, &[ // 2 × 3 + 10%, of all that came before
+            CalcButtons::Num2, CalcButtons::Multiply, CalcButtons::Num3, CalcButtons::Add,
+            CalcButtons::Num1, CalcButtons::Num0, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.sum, "6.6");
+    }
+
+    #[test]
+    fn check_business_operators_take_b_as_is() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Business);
+        let display = press_all(&mut engine, &[ // Cost 200 at a 20% margin
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Markup,
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.sum, "250");
+        let display = press_all(&mut engine, &[ // 200 to 230
+            CalcButtons::Clear, CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::PercentChange,
+            CalcButtons::Num2, CalcButtons::Num3, CalcButtons::Num0, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.sum, "15");
+    }
+
+    #[test]
+    fn check_ignored_in_programmer_mode() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Programmer);
+        let display = press_all(&mut engine, &[CalcButtons::Num5, CalcButtons::Num0, CalcButtons::Percent]);
+        assert_eq!(display.input, "50");
+        assert_eq!(display.error, None);
+        let display = press_all(&mut engine, &[CalcButtons::Add, CalcButtons::Num5, CalcButtons::Percent, CalcButtons::Sum]);
+        assert_eq!(display.sum, "55");
+    }
+
+    #[test]
+    fn check_standalone() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[CalcButtons::Num1, CalcButtons::Num5, CalcButtons::Percent]);
+        assert_eq!(display.input, "0.15");
+        let display = press_all(&mut engine, &[CalcButtons::Multiply, CalcButtons::Num4, CalcButtons::Sum]);
+        assert_eq!(display.sum, "0.6");
+        let display = press_all(&mut engine, &[CalcButtons::Percent]); // On a result
+        assert_eq!(display.sum, "0.006");
+    }
+
+    #[test]
+    fn check_rpn() {
+        let mut engine = CalcEngine::new();
+        engine.set_entry_mode(EntryMode::Rpn);
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Sum,
+            CalcButtons::Num1, CalcButtons::Num5, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.sum, "30");
+        assert_eq!(engine.last_x, 15.0);
+        let display = press_all(&mut engine, &[CalcButtons::Add]); // Y stayed
+        assert_eq!(display.sum, "230");
+    }
+
+    #[test]
+    fn check_tape_and_repeat() {
+        let mut engine = CalcEngine::new();
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Add,
+            CalcButtons::Num1, CalcButtons::Num5, CalcButtons::Percent,
+        ]);
+        assert_eq!(engine.history.entries[0].tape, "200 + 30 = 230");
+        let display = press_all(&mut engine, &[CalcButtons::Sum]); // = repeats the step the % made
+        assert_eq!(display.sum, "260");
+    }
+
+    #[test]
+    fn check_divide_by_zero() {
+        let mut engine = CalcEngine::new();
+        let display = press_all(&mut engine, &[
+            CalcButtons::Num5, CalcButtons::Divide, CalcButtons::Num0, CalcButtons::Percent,
+        ]);
+        assert_eq!(display.error, Some(CalcError::DivideByZero));
+    }
+
+    #[test]
+    fn check_business_grand_total() {
+        let mut engine = CalcEngine::new();
+        engine.set_mode(CalcMode::Business);
+        press_all(&mut engine, &[
+            CalcButtons::Num2, CalcButtons::Num0, CalcButtons::Num0, CalcButtons::Add,
+            CalcButtons::Num1, CalcButtons::Num5, CalcButtons::Percent,
+        ]);
+        assert_eq!(engine.business.grand_total, 230.0);
+    }
+}
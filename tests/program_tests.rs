@@ -0,0 +1,122 @@
+use calc_sim::calculator::CalcButtons;
+use calc_sim::number::Number;
+use calc_sim::program::{Condition, ProgramWait, Programs, RunState, Step};
+
+#[cfg(test)]
+mod program_test_steps {
+    use super::*;
+
+    #[test]
+    fn check_labels_parse_back() {
+        let steps: Vec<Step> = vec![
+            Step::Key(CalcButtons::Num7), Step::Key(CalcButtons::Register(3)), Step::Label(2),
+            Step::Goto(2), Step::Test(Condition::NotZero), Step::Stop,
+        ];
+        for step in steps {
+            assert_eq!(Step::parse(&step.label()), Some(step));
+        }
+        assert_eq!(Step::parse("LBL 12"), None);
+        assert_eq!(Step::parse("Nothing"), None);
+    }
+
+    #[test]
+    fn check_conditions() {
+        let x: Number = Number::Real("-2.5".parse().unwrap());
+        assert!(Condition::Negative.holds(x, 10));
+        assert!(Condition::NotZero.holds(x, 10));
+        assert!(!Condition::Positive.holds(x, 10));
+        assert!(Condition::Zero.holds(Number::ZERO, 10));
+        assert!(!Condition::Positive.holds(Number::ZERO, 10));
+    }
+}
+
+#[cfg(test)]
+mod program_test_file {
+    use super::*;
+
+    #[test]
+    fn check_text_round_trip() {
+        let mut programs = Programs::new();
+        programs.slots[1] = vec![Step::Key(CalcButtons::Add), Step::Label(0), Step::Test(Condition::Zero)];
+        let text: String = programs.to_text();
+        assert!(text.ends_with("1,Add,LBL 0,x=0?\n"));
+        assert_eq!(Programs::parse(&text).unwrap().slots, programs.slots);
+    }
+
+    #[test]
+    fn check_bad_lines() {
+        assert_eq!(Programs::parse("# comment\n\n3,Num1\n").unwrap().slots[3], vec![Step::Key(CalcButtons::Num1)]);
+        assert_eq!(Programs::parse("12,Num1\n").unwrap_err(), "line 1: program is not 0 to 9");
+        assert_eq!(Programs::parse("0,Num1,Nope\n").unwrap_err(), "line 1: unknown step Nope");
+    }
+
+    #[test]
+    fn check_save_makes_folder() {
+        let dir = std::env::temp_dir().join(format!("calc_sim_programs_{}", std::process::id()));
+        let path: String = dir.join("user").join("programs.txt").to_string_lossy().into_owned();
+        let mut programs = Programs::new();
+        programs.slots[2] = vec![Step::Key(CalcButtons::Num4)];
+        programs.save(&path).unwrap();
+        assert_eq!(Programs::load(&path).unwrap().slots, programs.slots);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod program_test_run {
+    use super::*;
+
+    #[test]
+    fn check_recording() {
+        let mut programs = Programs::new();
+        programs.complete(ProgramWait::Record, 4);
+        assert!(programs.recording);
+        assert_eq!(programs.line(), Some("4-00".to_string()));
+        programs.record(Step::Key(CalcButtons::Num2));
+        programs.record(Step::Key(CalcButtons::Add));
+        assert_eq!(programs.line(), Some("4-02 Add".to_string()));
+        programs.unrecord();
+        assert_eq!(programs.slots[4], vec![Step::Key(CalcButtons::Num2)]);
+        assert_eq!(programs.status(), Some("PRGM 4".to_string()));
+    }
+
+    #[test]
+    fn check_skip_and_goto() {
+        let mut programs = Programs::new();
+        programs.slots[0] = vec![
+            Step::Test(Condition::Zero), Step::Goto(1), Step::Key(CalcButtons::Num1), Step::Label(1), Step::Key(CalcButtons::Num2),
+        ];
+        programs.run_stop();
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(Some(CalcButtons::Num2))); // x=0? holds, so GTO 1
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(None));
+        assert_eq!(programs.run, None);
+        programs.run_stop();
+        let x: Number = Number::Real("5".parse().unwrap());
+        assert_eq!(programs.next_key(x, 10), Ok(Some(CalcButtons::Num1))); // Fails, GTO 1 is skipped
+        assert_eq!(programs.next_key(x, 10), Ok(Some(CalcButtons::Num2)));
+    }
+
+    #[test]
+    fn check_single_step_and_pause() {
+        let mut programs = Programs::new();
+        programs.slots[0] = vec![Step::Key(CalcButtons::Num1), Step::Stop, Step::Key(CalcButtons::Num2)];
+        programs.single_step();
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(Some(CalcButtons::Num1)));
+        assert_eq!(programs.run.unwrap().state, RunState::Paused);
+        assert_eq!(programs.status(), Some("0-02".to_string()));
+        programs.run_stop();
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(None)); // R/S in the program pauses it
+        assert!(!programs.running());
+        programs.run_stop();
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(Some(CalcButtons::Num2)));
+    }
+
+    #[test]
+    fn check_endless_loop_pauses() {
+        let mut programs = Programs::new();
+        programs.slots[0] = vec![Step::Label(0), Step::Goto(0)];
+        programs.run_stop();
+        assert_eq!(programs.next_key(Number::ZERO, 10), Ok(None));
+        assert_eq!(programs.run.unwrap().state, RunState::Paused);
+    }
+}
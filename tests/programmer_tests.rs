@@ -0,0 +1,60 @@
+use calc_sim::{CalcError, PendingOp};
+use calc_sim::programmer::{Base, IntFormat, WordSize};
+
+#[cfg(test)]
+mod programmer_test_format {
+    use super::*;
+
+    fn format(base: Base, word: WordSize, signed: bool) -> IntFormat {
+        IntFormat { base, word, signed }
+    }
+
+    #[test]
+    fn check_wraparound() {
+        let byte = format(Base::Dec, WordSize::Bits8, false);
+        assert_eq!(byte.apply(PendingOp::Add, 250, 10), Ok(4));
+        assert_eq!(byte.apply(PendingOp::Subtract, 0, 1), Ok(255));
+
+        let signed = format(Base::Dec, WordSize::Bits8, true);
+        assert_eq!(signed.apply(PendingOp::Add, 127, 1), Ok(-128));
+        assert_eq!(signed.apply(PendingOp::Multiply, 16, 16), Ok(0));
+
+        let qword = format(Base::Dec, WordSize::Bits64, false);
+        assert_eq!(qword.apply(PendingOp::Multiply, u64::MAX as i128, u64::MAX as i128), Ok(1));
+    }
+
+    #[test]
+    fn check_bitwise() {
+        let word = format(Base::Hex, WordSize::Bits16, false);
+        assert_eq!(word.apply(PendingOp::And, 0xF0F0, 0xFF00), Ok(0xF000));
+        assert_eq!(word.apply(PendingOp::Or, 0xF0F0, 0x0F00), Ok(0xFFF0));
+        assert_eq!(word.apply(PendingOp::Xor, 0xFFFF, 0x00FF), Ok(0xFF00));
+        assert_eq!(word.apply(PendingOp::ShiftLeft, 0x8001, 1), Ok(0x0002));
+        assert_eq!(word.apply(PendingOp::ShiftRight, 0x8000, 15), Ok(1));
+        assert_eq!(word.apply(PendingOp::ShiftLeft, 1, 16), Ok(0));
+        assert_eq!(word.apply(PendingOp::Modulo, 17, 5), Ok(2));
+        assert_eq!(word.apply(PendingOp::Divide, 17, 5), Ok(3));
+        assert_eq!(word.apply(PendingOp::Modulo, 17, 0), Err(CalcError::DivideByZero));
+
+        let signed = format(Base::Dec, WordSize::Bits8, true);
+        assert_eq!(signed.apply(PendingOp::ShiftRight, -128, 7), Ok(-1)); // Arithmetic shift keeps the sign
+        assert_eq!(signed.apply(PendingOp::Divide, -7, 2), Ok(-3));
+    }
+
+    #[test]
+    fn check_format() {
+        assert_eq!(format(Base::Hex, WordSize::Bits8, true).format(-1), "FF");
+        assert_eq!(format(Base::Bin, WordSize::Bits8, false).format(5), "101");
+        assert_eq!(format(Base::Oct, WordSize::Bits16, false).format(64), "100");
+        assert_eq!(format(Base::Dec, WordSize::Bits32, true).format(-42), "-42");
+        assert_eq!(format(Base::Hex, WordSize::Bits32, true).label(), "HEX i32");
+    }
+
+    #[test]
+    fn check_max_digits() {
+        assert_eq!(format(Base::Bin, WordSize::Bits64, true).max_digits(), 64);
+        assert_eq!(format(Base::Hex, WordSize::Bits32, true).max_digits(), 8);
+        assert_eq!(format(Base::Oct, WordSize::Bits8, true).max_digits(), 3);
+        assert_eq!(format(Base::Dec, WordSize::Bits16, true).max_digits(), 5);
+    }
+}
@@ -0,0 +1,133 @@
+use calc_sim::CalcError;
+use calc_sim::decimal::Decimal;
+use calc_sim::stats::{Sample, Statistic, Stats};
+
+#[cfg(test)]
+mod stats_test_summary {
+    use super::*;
+
+    fn single(values: &[&str]) -> Stats {
+        let mut stats = Stats::new();
+        for value in values {
+            stats.add(Sample { x: value.parse::<Decimal>().unwrap(), y: None });
+        }
+        stats
+    }
+
+    fn paired(values: &[(&str, &str)]) -> Stats {
+        let mut stats = Stats::new();
+        for (x, y) in values {
+            stats.add(Sample { x: x.parse::<Decimal>().unwrap(), y: Some(y.parse::<Decimal>().unwrap()) });
+        }
+        stats
+    }
+
+    fn get(stats: &Stats, stat: Statistic) -> String {
+        stats.get(stat, 12).unwrap().to_string()
+    }
+
+    #[test]
+    fn check_single_variable() {
+        let stats = single(&["2", "4", "4", "4", "5", "5", "7", "9"]);
+        assert_eq!(get(&stats, Statistic::Count), "8");
+        assert_eq!(get(&stats, Statistic::Mean), "5");
+        assert_eq!(get(&stats, Statistic::Sum), "40");
+        assert_eq!(get(&stats, Statistic::SumSquares), "232");
+        assert_eq!(get(&stats, Statistic::PopulationSd), "2");
+        assert_eq!(get(&stats, Statistic::SampleSd), "2.138089935299");
+    }
+
+    #[test]
+    fn check_sd_past_f64_digits() {
+        let stats = single(&["0", "2", "4"]);
+        assert_eq!(stats.get(Statistic::PopulationSd, 22).unwrap().to_string(), "1.6329931618554520654649"); // √(8/3)
+    }
+
+    #[test]
+    fn check_too_few_samples() {
+        assert_eq!(get(&Stats::new(), Statistic::Count), "0");
+        assert_eq!(get(&Stats::new(), Statistic::Sum), "0");
+        assert_eq!(Stats::new().get(Statistic::Mean, 12), Err(CalcError::Domain));
+        assert_eq!(single(&["3"]).get(Statistic::SampleSd, 12), Err(CalcError::Domain));
+        assert_eq!(get(&single(&["3"]), Statistic::PopulationSd), "0");
+    }
+
+    #[test]
+    fn check_linear_regression() {
+        let stats = paired(&[("1", "3"), ("2", "5"), ("3", "7"), ("4", "9")]);
+        assert_eq!(get(&stats, Statistic::Slope), "2");
+        assert_eq!(get(&stats, Statistic::Intercept), "1");
+        assert_eq!(get(&stats, Statistic::Correlation), "1");
+        let stats = paired(&[("1", "2"), ("2", "1"), ("3", "4"), ("4", "3")]);
+        assert_eq!(get(&stats, Statistic::Slope), "0.6");
+        assert_eq!(get(&stats, Statistic::Intercept), "1");
+        assert_eq!(get(&stats, Statistic::Correlation), "0.6");
+    }
+
+    #[test]
+    fn check_regression_needs_pairs() {
+        let mut stats = paired(&[("1", "3"), ("2", "5")]);
+        stats.add(Sample { x: Decimal::from(3), y: None });
+        assert_eq!(stats.get(Statistic::Slope, 12), Err(CalcError::Domain));
+        let stats = paired(&[("2", "3"), ("2", "5")]);
+        assert_eq!(stats.get(Statistic::Slope, 12), Err(CalcError::Domain)); // A vertical line
+        let stats = paired(&[("1", "5"), ("2", "5")]);
+        assert_eq!(stats.get(Statistic::Correlation, 12), Err(CalcError::Domain));
+    }
+}
+
+#[cfg(test)]
+mod stats_test_review {
+    use super::*;
+
+    fn sample(x: i32) -> Sample {
+        Sample { x: Decimal::from(x), y: None }
+    }
+
+    #[test]
+    fn check_remove_last_match() {
+        let mut stats = Stats::new();
+        for x in [1, 2, 1] {
+            stats.add(sample(x));
+        }
+        stats.remove(sample(1)).unwrap();
+        assert_eq!(stats.samples, [sample(1), sample(2)]);
+        assert_eq!(stats.remove(sample(5)), Err(CalcError::InvalidInput));
+    }
+
+    #[test]
+    fn check_review_steps() {
+        let mut stats = Stats::new();
+        stats.review_next();
+        assert_eq!(stats.review, None); // Nothing to review
+        stats.add(sample(1));
+        stats.add(Sample { x: Decimal::from(2), y: Some(Decimal::from(3)) });
+        stats.review_next();
+        assert_eq!(stats.reviewing(), Some("#1 1".to_string()));
+        stats.review_next();
+        assert_eq!(stats.reviewing(), Some("#2 2,3".to_string()));
+        assert_eq!(stats.label(), "#2/2");
+        stats.review_next();
+        assert_eq!(stats.review, None);
+        assert_eq!(stats.label(), "n=2");
+        stats.review_prev();
+        assert_eq!(stats.review, Some(1));
+    }
+
+    #[test]
+    fn check_review_edits() {
+        let mut stats = Stats::new();
+        for x in [1, 2, 3] {
+            stats.add(sample(x));
+        }
+        stats.review_next();
+        stats.review_next();
+        stats.add(sample(5)); // Replaces #2
+        assert_eq!(stats.samples, [sample(1), sample(5), sample(3)]);
+        assert_eq!(stats.review, None);
+        stats.review_prev();
+        stats.remove(sample(0)).unwrap(); // Whichever sample is under review
+        assert_eq!(stats.samples, [sample(1), sample(5)]);
+        assert_eq!(stats.review, Some(1));
+    }
+}
@@ -0,0 +1,119 @@
+use calc_sim::CalcError;
+use calc_sim::decimal::Decimal;
+use calc_sim::tvm::{AmortField, Tvm, TvmVar};
+
+fn dec(text: &str) -> Decimal {
+    text.parse::<Decimal>().unwrap()
+}
+
+fn loan() -> Tvm { // 30 years of monthly payments on 200000 at 6%
+    let mut tvm = Tvm::new();
+    tvm.set(TvmVar::Periods, dec("360"));
+    tvm.set(TvmVar::Rate, dec("6"));
+    tvm.set(TvmVar::PresentValue, dec("200000"));
+    tvm.set(TvmVar::FutureValue, Decimal::ZERO);
+    tvm
+}
+
+#[cfg(test)]
+mod tvm_test_solve {
+    use super::*;
+
+    #[test]
+    fn check_payment() {
+        assert_eq!(loan().solve(TvmVar::Payment, 2), Ok(dec("-1199.1")));
+    }
+
+    #[test]
+    fn check_future_value() {
+        let mut tvm = Tvm::new();
+        tvm.per_year = 1;
+        tvm.set(TvmVar::Periods, dec("10"));
+        tvm.set(TvmVar::Rate, dec("5"));
+        tvm.set(TvmVar::PresentValue, dec("-1000"));
+        assert_eq!(tvm.solve(TvmVar::FutureValue, 2), Ok(dec("1628.89")));
+        tvm.set(TvmVar::FutureValue, dec("1628.894626777442"));
+        assert_eq!(tvm.solve(TvmVar::PresentValue, 6), Ok(dec("-1000")));
+    }
+
+    #[test]
+    fn check_rate_and_periods() {
+        let mut tvm = loan();
+        tvm.set(TvmVar::Payment, dec("-1199.101050304"));
+        assert_eq!(tvm.solve(TvmVar::Rate, 6), Ok(dec("6")));
+        assert_eq!(tvm.solve(TvmVar::Periods, 4), Ok(dec("360")));
+    }
+
+    #[test]
+    fn check_begin_mode() {
+        let mut tvm = loan();
+        tvm.begin = true;
+        assert_eq!(tvm.solve(TvmVar::Payment, 2), Ok(dec("-1193.14"))); // A month's less interest on each
+    }
+
+    #[test]
+    fn check_zero_rate() {
+        let mut tvm = Tvm::new();
+        tvm.set(TvmVar::Periods, dec("10"));
+        tvm.set(TvmVar::PresentValue, dec("1000"));
+        assert_eq!(tvm.solve(TvmVar::Payment, 2), Ok(dec("-100")));
+        tvm.set(TvmVar::Payment, dec("-50"));
+        assert_eq!(tvm.solve(TvmVar::Periods, 2), Ok(dec("20")));
+    }
+
+    #[test]
+    fn check_no_solution() {
+        let mut tvm = loan();
+        tvm.set(TvmVar::Payment, dec("-500")); // Less than the interest, the loan is never paid off
+        assert_eq!(tvm.solve(TvmVar::Periods, 2), Err(CalcError::NoSolution));
+        tvm.set(TvmVar::Payment, dec("1000")); // Money in on both sides
+        assert_eq!(tvm.solve(TvmVar::Rate, 2), Err(CalcError::NoSolution));
+        tvm.set(TvmVar::Periods, Decimal::ZERO);
+        assert_eq!(tvm.solve(TvmVar::Payment, 2), Err(CalcError::NoSolution));
+    }
+
+    #[test]
+    fn check_per_year() {
+        let mut tvm = Tvm::new();
+        assert_eq!(tvm.set_per_year(dec("4")), Ok(()));
+        assert_eq!(tvm.per_year, 4);
+        assert_eq!(tvm.set_per_year(dec("2.5")), Err(CalcError::Domain));
+        assert_eq!(tvm.set_per_year(Decimal::ZERO), Err(CalcError::Domain));
+        tvm.begin = true;
+        tvm.set(TvmVar::Rate, dec("3"));
+        tvm.clear();
+        assert_eq!((tvm.rate, tvm.per_year, tvm.begin), (Decimal::ZERO, 4, true)); // Settings stay
+    }
+}
+
+#[cfg(test)]
+mod tvm_test_amortize {
+    use super::*;
+
+    #[test]
+    fn check_first_period() {
+        let mut tvm = loan();
+        tvm.set(TvmVar::Payment, dec("-1199.1"));
+        let row = tvm.amortize(1, 2).unwrap();
+        assert_eq!((row.interest, row.principal, row.balance), (dec("-1000"), dec("-199.1"), dec("199800.9")));
+    }
+
+    #[test]
+    fn check_paid_off() {
+        let mut tvm = loan();
+        tvm.set(TvmVar::Payment, tvm.solve(TvmVar::Payment, 10).unwrap());
+        assert_eq!(tvm.amortize(360, 2).unwrap().balance, Decimal::ZERO);
+    }
+
+    #[test]
+    fn check_viewer_line() {
+        let mut tvm = loan();
+        tvm.set(TvmVar::Payment, dec("-1199.1"));
+        assert!(tvm.open_amort());
+        assert_eq!(tvm.amort_line(2), Some("P1 INT -1000".to_string()));
+        tvm.amort = Some((1, AmortField::Balance));
+        assert_eq!(tvm.amort_line(2), Some("P1 BAL 199800.9".to_string()));
+        tvm.set(TvmVar::Periods, Decimal::ZERO);
+        assert!(!tvm.open_amort());
+    }
+}
@@ -0,0 +1,31 @@
+use calc_sim::CalcEngine;
+use calc_sim::calculator::CalcButtons;
+use calc_sim::undo::{Snapshot, UndoStack, UNDO_DEPTH};
+
+#[cfg(test)]
+mod undo_test_stack {
+    use super::*;
+
+    #[test]
+    fn check_depth_bounded() {
+        let mut engine = CalcEngine::new();
+        let mut stack = UndoStack::new();
+        for _ in 0..UNDO_DEPTH + 10 {
+            stack.push(Snapshot::take(&engine));
+            engine.press(CalcButtons::Num1);
+        }
+        assert_eq!(stack.depth(), (UNDO_DEPTH, 0));
+    }
+
+    #[test]
+    fn check_push_clears_redo() {
+        let engine = CalcEngine::new();
+        let mut stack = UndoStack::new();
+        stack.push(Snapshot::take(&engine));
+        assert!(stack.undo(Snapshot::take(&engine)).is_some());
+        assert_eq!(stack.depth(), (0, 1));
+        assert!(stack.undo(Snapshot::take(&engine)).is_none());
+        stack.push(Snapshot::take(&engine));
+        assert_eq!(stack.depth(), (1, 0));
+    }
+}
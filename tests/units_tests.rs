@@ -0,0 +1,66 @@
+use calc_sim::CalcError;
+use calc_sim::csv::CsvTable;
+use calc_sim::decimal::Decimal;
+use calc_sim::units::{Converter, UnitTable, UNITS_PATH};
+
+#[cfg(test)]
+mod units_test_table {
+    use super::*;
+
+    fn converter(category: &str, from: &str, to: &str) -> Converter {
+        let mut converter = Converter::new(UnitTable::load(UNITS_PATH).unwrap());
+        while converter.table.categories[converter.category].name != category {
+            converter.next_category();
+        }
+        let units = &converter.table.categories[converter.category].units;
+        converter.from = units.iter().position(|unit| unit.symbol == from).unwrap();
+        converter.to = units.iter().position(|unit| unit.symbol == to).unwrap();
+        converter
+    }
+
+    fn convert(category: &str, from: &str, to: &str, value: &str) -> String {
+        converter(category, from, to).convert(value.parse::<Decimal>().unwrap(), 12).unwrap().to_string()
+    }
+
+    #[test]
+    fn check_asset_loads() {
+        let table = UnitTable::load(UNITS_PATH).unwrap();
+        let names: Vec<&str> = table.categories.iter().map(|category| category.name.as_str()).collect();
+        assert_eq!(names, ["length", "mass", "volume", "temperature", "time", "data", "energy"]);
+    }
+
+    #[test]
+    fn check_linear_units() {
+        assert_eq!(convert("length", "mi", "km", "1"), "1.609344");
+        assert_eq!(convert("length", "ft", "in", "3"), "36");
+        assert_eq!(convert("mass", "lb", "oz", "1"), "16");
+        assert_eq!(convert("volume", "gal", "cup", "1"), "16");
+        assert_eq!(convert("time", "h", "s", "1.5"), "5400");
+        assert_eq!(convert("data", "b", "B", "16"), "2");
+        assert_eq!(convert("data", "GiB", "MiB", "1"), "1024");
+        assert_eq!(convert("energy", "kWh", "kcal", "1"), "860.420650095602");
+        assert_eq!(convert("energy", "J", "eV", "1"), "6241509074460762607.77624098093"); // Exact, the electronvolt's small factor divides without f64
+    }
+
+    #[test]
+    fn check_temperature_is_affine() {
+        assert_eq!(convert("temperature", "C", "F", "100"), "212");
+        assert_eq!(convert("temperature", "F", "C", "-40"), "-40");
+        assert_eq!(convert("temperature", "C", "K", "-273.15"), "0");
+        assert_eq!(convert("temperature", "F", "K", "32"), "273.15");
+    }
+
+    #[test]
+    fn check_bad_lines() {
+        assert!(UnitTable::parse("length,metre,m").is_err());
+        assert!(UnitTable::parse("length,metre,m,0").is_err());
+        assert!(UnitTable::parse("temperature,celsius,C,1,warm").is_err());
+    }
+
+    #[test]
+    fn check_no_units() {
+        let converter = Converter::default();
+        assert_eq!(converter.convert(Decimal::ONE, 12), Err(CalcError::InvalidInput));
+        assert_eq!(converter.label(), "NO UNITS");
+    }
+}